@@ -4,6 +4,11 @@
 
 # 3 yoctoNear = 3000000000000000000000000
 
+# pickup tomorrow, delivery the day after, in nanoseconds
+NOW=$(date +%s)
+PICKUP_WINDOW='{"start": '$(( (NOW + 86400) * 1000000000 ))', "end": '$(( (NOW + 93600) * 1000000000 ))'}'
+DELIVERY_WINDOW='{"start": '$(( (NOW + 172800) * 1000000000 ))', "end": '$(( (NOW + 180000) * 1000000000 ))'}'
+
 echo ">> Running contract methods"
 echo ">> ..."
 echo ">> ..."
@@ -31,6 +36,30 @@ echo ">> ..."
 near call dev-1665068266464-27599380827838 get_admin_by_account_id '{"account_id": "millefolium.testnet"}' --accountId millefolium.testnet
 echo ">> ..."
 echo ">> ..."
+echo ">> Running contract methods:create_service"
+echo ">> ..."
+echo ">> ..."
+near call dev-1665068266464-27599380827838 create_service '{
+    "service_id": "wash_and_fold", "name": "Wash & Fold",
+    "price_per_kg_in_yocto_near": "1000000000000000000000000", "price_per_item_in_yocto_near": "0"
+}' --accountId millefolium.testnet
+echo ">> ..."
+echo ">> ..."
+echo ">> Running contract methods:create_pickup_slot"
+echo ">> ..."
+echo ">> ..."
+near call dev-1665068266464-27599380827838 create_pickup_slot '{
+    "window": '"$PICKUP_WINDOW"', "max_orders": 10, "max_weight_in_grams": 50000
+}' --accountId millefolium.testnet
+echo ">> ..."
+echo ">> ..."
+echo ">> Running contract methods:add_accepted_token"
+echo ">> ..."
+echo ">> ..."
+near call dev-1665068266464-27599380827838 add_accepted_token '{"token_id": "usdc.fakes.testnet", "symbol": "USDC", "units_per_near": "2000000"}' --accountId millefolium.testnet
+near view dev-1665068266464-27599380827838 get_accepted_tokens --accountId millefolium.testnet
+echo ">> ..."
+echo ">> ..."
 echo ">> Running contract methods:create_customer"
 echo ">> ..."
 echo ">> ..."
@@ -77,32 +106,34 @@ echo ">> Running contract methods:create_order"
 echo ">> ..."
 echo ">> ..."
 near call dev-1665068266464-27599380827838 create_order '{
-    "id": "fghjk", "customer_id": "envoy.testnet", "description": "2 pants",
-    "weight_in_grams": 3500, "price_in_yocto_near": "3000000000000000000000000"
-}' --deposit 4 --accountId envoy.testnet
+    "customer_id": "envoy.testnet", "description": "2 pants",
+    "items": [{"service_id": "wash_and_fold", "garment_type": "Pants", "quantity": 2, "weight_in_grams": 3500, "special_instructions": ""}],
+    "pickup_window": '"$PICKUP_WINDOW"', "delivery_window": '"$DELIVERY_WINDOW"'
+}' --deposit 5 --accountId envoy.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:create_order"
 echo ">> ..."
 echo ">> ..."
 near call dev-1665068266464-27599380827838 create_order '{
-    "id": "sxdhjk", "customer_id": "sunroz.testnet", "description": "2 shirts",
-    "weight_in_grams": 3500, "price_in_yocto_near": "3000000000000000000000000"
-}' --deposit 4 --accountId sunroz.testnet
+    "customer_id": "sunroz.testnet", "description": "2 shirts",
+    "items": [{"service_id": "wash_and_fold", "garment_type": "Shirt", "quantity": 2, "weight_in_grams": 3500, "special_instructions": ""}],
+    "pickup_window": '"$PICKUP_WINDOW"', "delivery_window": '"$DELIVERY_WINDOW"', "payment_type": "CashOnDelivery"
+}' --deposit 2 --accountId sunroz.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:check_order_exists"
-near view dev-1665068266464-27599380827838 check_order_exists '{"order_id": "fghjk"}' --accountId millefolium.testnet
+near view dev-1665068266464-27599380827838 check_order_exists '{"order_id": "WK-1"}' --accountId millefolium.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:get_order_by_id"
-near call dev-1665068266464-27599380827838 get_order_by_id '{"order_id": "fghjk"}' --accountId envoy.testnet
+near call dev-1665068266464-27599380827838 get_order_by_id '{"order_id": "WK-1"}' --accountId envoy.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:update_order_status"
 echo ">> ..."
 echo ">> ..."
-near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "fghjk", "order_status": "InProgress"}' --accountId millefolium.testnet
+near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "WK-1", "order_status": "PickedUp"}' --accountId millefolium.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:get_order_list"
@@ -120,18 +151,34 @@ echo ">> ..."
 echo ">> Running contract methods:update_order_status"
 echo ">> ..."
 echo ">> ..."
-near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "sxdhjk", "order_status": "Cancelled"}' --accountId millefolium.testnet
+near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "WK-2", "order_status": "Cancelled"}' --accountId millefolium.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:update_order_status"
 echo ">> ..."
 echo ">> ..."
-near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "Y_cKGw9kp2Pp23cmYUBqS", "order_status": "Delivered"}' --accountId millefolium.testnet
+near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "WK-1", "order_status": "Washing"}' --accountId millefolium.testnet
+near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "WK-1", "order_status": "ReadyForDelivery"}' --accountId millefolium.testnet
+near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "WK-1", "order_status": "OutForDelivery"}' --accountId millefolium.testnet
+near call dev-1665068266464-27599380827838 update_order_status '{"order_id": "WK-1", "order_status": "Delivered"}' --accountId millefolium.testnet
 echo ">> ..."
 echo ">> ..."
 echo ">> Running contract methods:submit_feedback"
 echo ">> ..."
 echo ">> ..."
-near call dev-1665068266464-27599380827838 submit_feedback '{"order_id": "fghjk", "customer_feedback": "Good", "customer_feedback_comment": "very good service."}' --deposit 1 --accountId envoy.testnet
+near call dev-1665068266464-27599380827838 submit_feedback '{"order_id": "WK-1", "rating": {"wash_quality": 5, "punctuality": 4, "staff_behaviour": 4}, "customer_feedback_comment": "very good service."}' --deposit 1 --accountId envoy.testnet
+echo ">> ..."
+echo ">> ..."
+echo ">> Running contract methods:set_feedback_comment_hidden"
+echo ">> ..."
+echo ">> ..."
+near call dev-1665068266464-27599380827838 set_feedback_comment_hidden '{"order_id": "WK-1", "is_hidden": false}' --accountId millefolium.testnet
+echo ">> ..."
+echo ">> ..."
+echo ">> Running contract methods:reply_to_review"
+echo ">> ..."
+echo ">> ..."
+near call dev-1665068266464-27599380827838 reply_to_review '{"order_id": "WK-1", "body": "Thank you for your feedback!"}' --accountId millefolium.testnet
+near view dev-1665068266464-27599380827838 get_reviews '{"from_index": 0, "limit": 10}' --accountId sunroz.testnet
 echo ">> ..."
 echo ">> ..."
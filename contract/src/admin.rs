@@ -48,7 +48,7 @@ impl Contract {
 
       let exists: bool = self.check_is_admin(&account_id);
 
-      if exists == true {
+      if exists {
         env::panic_str("Account already exists.")
       }
 
@@ -73,7 +73,7 @@ impl Contract {
 
       let deleted_admin: Option<Admin>;
 
-      if exists == true {
+      if exists {
         deleted_admin = self.admin_lookup.remove(&account_id);
       } else {
         env::panic_str("Account not found.")
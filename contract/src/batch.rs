@@ -0,0 +1,52 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::OrderStatus;
+
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, env, log, require};
+
+pub const MAX_BATCH_STATUS_UPDATES: usize = 50;
+
+/**
+ * the outcome of one entry of a batch status update
+ **/
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderStatusUpdateResult {
+  pub order_id: String,
+  pub order_status: OrderStatus,
+  pub success: bool,
+  pub error: Option<String>,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Applies each (order ID, status) pair with the same rules and refunds as update_order_status.
+   * A rejected entry is reported in its result and does not affect the other entries.
+   * @return one result per entry, in the order given.
+   */
+    pub fn update_order_statuses(&mut self, updates: Vec<(String, OrderStatus)>) -> Vec<OrderStatusUpdateResult> {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can update order status.");
+      require!(!updates.is_empty() && updates.len() <= MAX_BATCH_STATUS_UPDATES, "Batch must have between 1 and 50 updates.");
+
+      let results: Vec<OrderStatusUpdateResult> = updates.into_iter().map(|(order_id, order_status)| {
+        let error: Option<String> = self.apply_order_status(&order_id, order_status, None).err();
+
+        OrderStatusUpdateResult {
+          order_id,
+          order_status,
+          success: error.is_none(),
+          error,
+        }
+      }).collect();
+
+      log!("updated {} of {} order statuses successfully", results.iter().filter(|result| result.success).count(), results.len());
+
+      results
+    }
+}
@@ -0,0 +1,111 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderStatus};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, env, log, require};
+use near_sdk::json_types::U128;
+
+pub const DEFAULT_REFUND_PERCENTAGE_AFTER_PICKUP: u8 = 50;
+
+/**
+ * refund policy for customer-initiated cancellations
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CancellationPolicy {
+  pub refund_percentage_after_pickup: u8,
+}
+
+impl Default for CancellationPolicy {
+  fn default() -> Self {
+    CancellationPolicy {
+      refund_percentage_after_pickup: DEFAULT_REFUND_PERCENTAGE_AFTER_PICKUP,
+    }
+  }
+}
+
+impl CancellationPolicy {
+  /**
+   * Calculates the refund for an order of the given paid amount cancelled by the customer at the given status.
+   * Orders are refunded in full before pickup, partially after pickup and can not be cancelled once washing starts.
+   * @return refund in yoctoNEAR, or None if the customer can no longer cancel.
+   */
+  pub fn calculate_refund(&self, order_status: OrderStatus, paid_in_yocto_near: u128) -> Option<u128> {
+    match order_status {
+      OrderStatus::PickupScheduled => Some(paid_in_yocto_near),
+      OrderStatus::PickedUp | OrderStatus::AwaitingTopUp => Some(
+        u128::checked_mul(paid_in_yocto_near, self.refund_percentage_after_pickup.into()).unwrap() / 100
+      ),
+      _ => None,
+    }
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * @return updated CancellationPolicy object.
+   */
+    pub fn set_cancellation_policy(&mut self, refund_percentage_after_pickup: u8) -> CancellationPolicy {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can set cancellation policy.");
+      require!(refund_percentage_after_pickup <= 100, "Refund percentage must not exceed 100.");
+
+      self.cancellation_policy = CancellationPolicy {
+        refund_percentage_after_pickup,
+      };
+
+      log!("updated cancellation policy successfully");
+
+      self.cancellation_policy.clone()
+    }
+
+    /**
+   * Gets the current cancellation policy.
+   * @return CancellationPolicy object.
+   */
+    pub fn get_cancellation_policy(&self) -> CancellationPolicy {
+      self.cancellation_policy.clone()
+    }
+
+    /**
+   * Customer cancels their own order for the given order ID.
+   * The refund follows the cancellation policy for the current stage, less the storage cost of the change unless the order is paid in tokens.
+   * The retained part of the payment is recorded on the order and paid out through settle_order.
+   * @return cancelled Order object for the given order ID.
+   */
+    pub fn cancel_order(&mut self, order_id: String, reason: Option<String>) -> Order {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let storage_used_before: u64 = env::storage_usage();
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(env::predecessor_account_id() == order.customer_id, "You can only cancel your own order.");
+
+      let refund: Option<u128> = self.cancellation_policy.calculate_refund(order.status, order.paid_in_yocto_near());
+
+      require!(refund.is_some(), "Order can no longer be cancelled.");
+
+      order.refunded_in_yocto_near = U128(refund.unwrap());
+      order.is_settled = refund.unwrap() == order.paid_in_yocto_near();
+
+      self.set_order_status(&mut order, OrderStatus::Cancelled, reason);
+
+      log!("cancelled order: {} successfully", &order_id);
+
+      let final_storage_cost = self.charge_order_storage(&order, storage_used_before);
+      let surplus = refund.unwrap().saturating_sub(final_storage_cost);
+
+      if surplus > 0 {
+        self.transfer_order_funds(&order, &order.customer_id, surplus, "surplus");
+      }
+
+      order
+    }
+}
@@ -0,0 +1,176 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{NewOrderItem, OrderItem, MAX_ORDER_ITEMS, MAX_SPECIAL_INSTRUCTIONS_LENGTH};
+use crate::char_count;
+use crate::Page;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, PanicOnDefault, Timestamp, env, log, require};
+use near_sdk::json_types::U128;
+
+pub const GRAMS_PER_KG: u128 = 1_000;
+
+/**
+ * a laundry service offered in the catalog
+ **/
+#[near_bindgen]
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone, PanicOnDefault)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Service {
+  pub id: String,
+  pub name: String,
+  pub price_per_kg_in_yocto_near: U128,
+  pub price_per_item_in_yocto_near: U128,
+  pub is_active: bool,
+  pub created: Timestamp,
+  pub updated: Timestamp,
+}
+
+#[near_bindgen]
+impl Service {
+  /**
+   * Creates a service for the given service ID.
+   * @return Service object for the given ID.
+   */
+  fn new(id: &String, name: &String, price_per_kg_in_yocto_near: &U128, price_per_item_in_yocto_near: &U128) -> Service {
+    Service {
+      id: id.to_owned(),
+      name: name.to_owned(),
+      price_per_kg_in_yocto_near: price_per_kg_in_yocto_near.to_owned(),
+      price_per_item_in_yocto_near: price_per_item_in_yocto_near.to_owned(),
+      is_active: true,
+      created: env::block_timestamp(),
+      updated: env::block_timestamp(),
+    }
+  }
+
+  /**
+   * Calculates the price of this service for the given weight and item count.
+   * @return price in yoctoNEAR.
+   */
+  pub fn calculate_price(&self, weight_in_grams: u32, quantity: u32) -> u128 {
+    let weight_price: u128 = self.price_per_kg_in_yocto_near.0
+      .checked_mul(weight_in_grams.into())
+      .unwrap()
+      / GRAMS_PER_KG;
+
+    let item_price: u128 = self.price_per_item_in_yocto_near.0
+      .checked_mul(quantity.into())
+      .unwrap();
+
+    u128::checked_add(weight_price, item_price).unwrap()
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * @return newly created Service object for the given service ID.
+   */
+    pub fn create_service(&mut self, service_id: String, name: String, price_per_kg_in_yocto_near: U128, price_per_item_in_yocto_near: U128) -> Service {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can create services.");
+      require!(!self.check_service_exists(&service_id), "Service already exists.");
+      require!(price_per_kg_in_yocto_near.0 > 0 || price_per_item_in_yocto_near.0 > 0, "Service must have a per-kg or a per-item rate.");
+
+      let service: Service = Service::new(&service_id, &name, &price_per_kg_in_yocto_near, &price_per_item_in_yocto_near);
+
+      self.services_umap.insert(&service_id, &service);
+
+      log!("created service: {} successfully", &service_id);
+
+      service
+    }
+
+    /**
+   * Only the admin can call this function.
+   * @return updated Service object for the given service ID.
+   */
+    pub fn update_service(&mut self, service_id: String, name: String, price_per_kg_in_yocto_near: U128, price_per_item_in_yocto_near: U128, is_active: bool) -> Service {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can update services.");
+      require!(price_per_kg_in_yocto_near.0 > 0 || price_per_item_in_yocto_near.0 > 0, "Service must have a per-kg or a per-item rate.");
+
+      let mut service: Service = self.get_service_by_id(service_id.clone());
+
+      service.name = name;
+      service.price_per_kg_in_yocto_near = price_per_kg_in_yocto_near;
+      service.price_per_item_in_yocto_near = price_per_item_in_yocto_near;
+      service.is_active = is_active;
+      service.updated = env::block_timestamp();
+
+      self.services_umap.insert(&service_id, &service);
+
+      log!("updated service: {} successfully", &service_id);
+
+      service
+    }
+
+    /**
+   * Checks if service with given ID exists.
+   * @return boolean for the given service ID.
+   */
+    pub fn check_service_exists(&self, service_id: &String) -> bool  {
+      self.services_umap.get(service_id).is_some()
+    }
+
+    /**
+   * Gets the service for the given service ID.
+   * @return Service object for the given service ID.
+   */
+    pub fn get_service_by_id(&self, service_id: String) -> Service  {
+      match self.services_umap.get(&service_id) {
+        Some(service) => service,
+        None => env::panic_str("Service does not exist.")
+      }
+    }
+
+    /**
+   * Gets the service catalog.
+   * @return a page of the Service list.
+   */
+    pub fn get_service_list(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Service>  {
+      Page::from_vector(self.services_umap.values_as_vector(), from_index, limit)
+    }
+
+    /**
+   * Prices each of the given line items from the catalog.
+   * A line is charged its service's per-kg rate for its weight plus the per-item rate for its quantity.
+   * @return list of priced order items.
+   */
+    pub(crate) fn price_order_items(&self, items: &[NewOrderItem]) -> Vec<OrderItem> {
+      require!(!items.is_empty(), "Order must have at least one item.");
+      require!(items.len() <= MAX_ORDER_ITEMS, "Order has too many items.");
+
+      items.iter().map(|item: &NewOrderItem| {
+        require!(item.quantity > 0 || item.weight_in_grams > 0, "Order item must have a quantity or a weight.");
+        require!(char_count(&item.special_instructions) <= MAX_SPECIAL_INSTRUCTIONS_LENGTH, "Special instructions are too long.");
+
+        let service: Service = self.get_service_by_id(item.service_id.clone());
+
+        require!(service.is_active, "Service is not available.");
+
+        OrderItem {
+          service_id: item.service_id.clone(),
+          garment_type: item.garment_type,
+          quantity: item.quantity,
+          weight_in_grams: item.weight_in_grams,
+          special_instructions: item.special_instructions.clone(),
+          price_in_yocto_near: U128(service.calculate_price(item.weight_in_grams, item.quantity)),
+        }
+      }).collect()
+    }
+
+    /**
+   * Quotes the price of an order before it is created.
+   * @return total price in yoctoNEAR for the given line items.
+   */
+    pub fn calculate_order_price(&self, items: Vec<NewOrderItem>) -> U128  {
+      let order_items: Vec<OrderItem> = self.price_order_items(&items);
+
+      U128(OrderItem::total_price(&order_items))
+    }
+}
@@ -0,0 +1,237 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::char_count;
+use crate::{Order, OrderStatus};
+use crate::Page;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, log, require, Promise};
+use near_sdk::json_types::U128;
+
+pub const MAX_CLAIM_DESCRIPTION_LENGTH: usize = 500;
+
+/**
+ * all available claim types
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ClaimType {
+  Damage = 1,
+  LostItem,
+}
+
+/**
+ * all available claim statuses
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ClaimStatus {
+  Pending = 1,
+  Approved,
+  Rejected,
+}
+
+/**
+ * a customer claim for compensation over damaged or lost line items of a delivered order
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Claim {
+  pub order_id: String,
+  pub customer_id: AccountId,
+  pub claim_type: ClaimType,
+  pub item_indexes: Vec<u32>,
+  pub description: String,
+  pub requested_in_yocto_near: U128,
+  pub approved_in_yocto_near: U128,
+  pub status: ClaimStatus,
+  pub review_note: Option<String>,
+  pub reviewed_by: Option<AccountId>,
+  pub created: Timestamp,
+  pub updated: Timestamp,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Adds the attached deposit to the reserve that approved claims are paid from.
+   * @return the new claims reserve.
+   */
+    #[payable]
+    pub fn fund_claims_reserve(&mut self) -> U128 {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can fund the claims reserve.");
+      require!(env::attached_deposit() > 0, "Deposited amount must be greater than 0.");
+
+      self.claims_reserve_in_yocto_near = u128::checked_add(self.claims_reserve_in_yocto_near, env::attached_deposit()).unwrap();
+
+      log!("funded claims reserve with: {} yN successfully", env::attached_deposit());
+
+      U128(self.claims_reserve_in_yocto_near)
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Withdraws unused funds from the claims reserve to the calling admin.
+   * @return the new claims reserve.
+   */
+    pub fn withdraw_claims_reserve(&mut self, amount: U128) -> U128 {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can withdraw from the claims reserve.");
+      require!(amount.0 > 0 && amount.0 <= self.claims_reserve_in_yocto_near, "Amount must be between 1 yN and the claims reserve.");
+
+      self.claims_reserve_in_yocto_near -= amount.0;
+
+      Promise::new(env::predecessor_account_id()).transfer(amount.0);
+      log!("Transferred claims reserve: {} yN to account_id: {}", amount.0, &env::predecessor_account_id());
+
+      U128(self.claims_reserve_in_yocto_near)
+    }
+
+    /**
+   * Gets the funds available for claim compensation.
+   * @return the claims reserve.
+   */
+    pub fn get_claims_reserve(&self) -> U128 {
+      U128(self.claims_reserve_in_yocto_near)
+    }
+
+    /**
+   * Customer files a claim over damaged or lost line items of their delivered order, given by their index in the order.
+   * @return newly created Claim object for the given order ID.
+   */
+    #[payable]
+    pub fn file_claim(&mut self, order_id: String, claim_type: ClaimType, item_indexes: Vec<u32>, description: String, requested_in_yocto_near: U128) -> Claim {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      let order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(env::predecessor_account_id() == order.customer_id, "You can only file claims for your own order.");
+      require!(deposit_amount >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(order.status == OrderStatus::Delivered, "Order must have Delivered status.");
+      require!(self.claims_umap.get(&order_id).is_none(), "Order already has a claim.");
+      require!(!item_indexes.is_empty(), "Claim must name at least one item.");
+      require!(item_indexes.iter().all(|index| (*index as usize) < order.items.len()), "Claim names an item that is not in the order.");
+      require!(item_indexes.iter().enumerate().all(|(position, index)| !item_indexes[..position].contains(index)), "Claim names an item more than once.");
+      require!(!description.is_empty() && char_count(&description) <= MAX_CLAIM_DESCRIPTION_LENGTH, "Claim description must be between 1 and 500 characters.");
+      require!(requested_in_yocto_near.0 > 0, "Requested amount must be greater than 0.");
+
+      let claim = Claim {
+        order_id: order_id.clone(),
+        customer_id: order.customer_id.clone(),
+        claim_type,
+        item_indexes,
+        description,
+        requested_in_yocto_near,
+        approved_in_yocto_near: U128(0),
+        status: ClaimStatus::Pending,
+        review_note: None,
+        reviewed_by: None,
+        created: env::block_timestamp(),
+        updated: env::block_timestamp(),
+      };
+
+      self.claims_umap.insert(&order_id, &claim);
+
+      log!("filed claim for order: {} successfully", &order_id);
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+      let surplus = u128::checked_sub(deposit_amount, final_storage_cost).unwrap();
+
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(surplus);
+        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      }
+
+      claim
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Approves up to the requested amount and pays it to the customer from the claims reserve.
+   * @return approved Claim object for the given order ID.
+   */
+    pub fn approve_claim(&mut self, order_id: String, approved_in_yocto_near: U128, review_note: Option<String>) -> Claim {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can review claims.");
+
+      let mut claim: Claim = self.get_claim(order_id.clone());
+
+      require!(claim.status == ClaimStatus::Pending, "Claim has already been reviewed.");
+      require!(approved_in_yocto_near.0 > 0 && approved_in_yocto_near.0 <= claim.requested_in_yocto_near.0, "Approved amount must be between 1 yN and the requested amount.");
+      require!(approved_in_yocto_near.0 <= self.claims_reserve_in_yocto_near, "Claims reserve is too low.");
+
+      self.claims_reserve_in_yocto_near -= approved_in_yocto_near.0;
+
+      claim.status = ClaimStatus::Approved;
+      claim.approved_in_yocto_near = approved_in_yocto_near;
+      claim.review_note = review_note;
+      claim.reviewed_by = Some(env::predecessor_account_id());
+      claim.updated = env::block_timestamp();
+
+      self.claims_umap.insert(&order_id, &claim);
+
+      log!("approved claim for order: {} successfully", &order_id);
+
+      Promise::new(claim.customer_id.clone()).transfer(approved_in_yocto_near.0);
+      log!("Transferred compensation: {} yN to account_id: {}", approved_in_yocto_near.0, &claim.customer_id);
+
+      claim
+    }
+
+    /**
+   * Only the admin can call this function.
+   * @return rejected Claim object for the given order ID.
+   */
+    pub fn reject_claim(&mut self, order_id: String, review_note: Option<String>) -> Claim {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can review claims.");
+
+      let mut claim: Claim = self.get_claim(order_id.clone());
+
+      require!(claim.status == ClaimStatus::Pending, "Claim has already been reviewed.");
+
+      claim.status = ClaimStatus::Rejected;
+      claim.review_note = review_note;
+      claim.reviewed_by = Some(env::predecessor_account_id());
+      claim.updated = env::block_timestamp();
+
+      self.claims_umap.insert(&order_id, &claim);
+
+      log!("rejected claim for order: {} successfully", &order_id);
+
+      claim
+    }
+
+    /**
+   * Only the owning customer or an admin can fetch the claim of the given order ID.
+   * @return Claim object for the given order ID.
+   */
+    pub fn get_claim(&self, order_id: String) -> Claim  {
+      let claim: Claim = match self.claims_umap.get(&order_id) {
+        Some(claim) => claim,
+        None => env::panic_str("Claim does not exist.")
+      };
+
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == claim.customer_id || self.check_is_admin(&caller), "You can only fetch claims of your own orders.");
+
+      claim
+    }
+
+    /**
+   * Only the admin can call this function.
+   * @return a page of the Claim list.
+   */
+    pub fn get_claim_list(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Claim>  {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can call this function.");
+
+      Page::from_vector(self.claims_umap.values_as_vector(), from_index, limit)
+    }
+}
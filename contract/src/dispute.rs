@@ -0,0 +1,279 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::char_count;
+use crate::{Order, OrderStatus};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, log, require, Promise};
+use near_sdk::json_types::U128;
+
+pub const DEFAULT_DISPUTE_WINDOW: u64 = 3 * 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_DISPUTE_REASON_LENGTH: usize = 500;
+pub const MAX_EVIDENCE_HASHES: usize = 10;
+pub const MAX_EVIDENCE_HASH_LENGTH: usize = 100;
+
+/**
+ * all available dispute statuses
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DisputeStatus {
+  Open = 1,
+  Resolved,
+}
+
+/**
+ * all available dispute resolutions
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum DisputeResolution {
+  FullRefund,
+  PartialRefund(U128),
+  Rejected,
+}
+
+/**
+ * a customer dispute over a delivered order
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Dispute {
+  pub order_id: String,
+  pub customer_id: AccountId,
+  pub reason: String,
+  pub evidence_hashes: Vec<String>,
+  pub status: DisputeStatus,
+  pub resolution: Option<DisputeResolution>,
+  pub resolution_note: Option<String>,
+  pub resolved_by: Option<AccountId>,
+  pub created: Timestamp,
+  pub updated: Timestamp,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Sets how long after delivery a customer can open a dispute, in nanoseconds.
+   * @return the new dispute window.
+   */
+    pub fn set_dispute_window(&mut self, dispute_window: u64) -> u64 {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can set dispute window.");
+
+      self.dispute_window = dispute_window;
+
+      log!("updated dispute window to: {} ns successfully", dispute_window);
+
+      self.dispute_window
+    }
+
+    /**
+   * Gets how long after delivery a customer can open a dispute, in nanoseconds.
+   * @return the dispute window.
+   */
+    pub fn get_dispute_window(&self) -> u64 {
+      self.dispute_window
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Appoints an arbiter who can resolve disputes.
+   */
+    pub fn add_arbiter(&mut self, account_id: AccountId) {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can appoint arbiters.");
+      require!(!self.check_is_arbiter(&account_id), "Account is already an arbiter.");
+
+      self.arbiters_lookup.insert(&account_id);
+
+      log!("appointed arbiter: {} successfully", &account_id);
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Removes an arbiter.
+   */
+    pub fn remove_arbiter(&mut self, account_id: AccountId) {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can remove arbiters.");
+      require!(self.check_is_arbiter(&account_id), "Account is not an arbiter.");
+
+      self.arbiters_lookup.remove(&account_id);
+
+      log!("removed arbiter: {} successfully", &account_id);
+    }
+
+    /**
+   * Checks if arbiter with given ID exists.
+   * @return boolean for the given ID.
+   */
+    pub fn check_is_arbiter(&self, account_id: &AccountId) -> bool  {
+      self.arbiters_lookup.contains(account_id)
+    }
+
+    /**
+   * Customer opens a dispute over their delivered order within the dispute window.
+   * Evidence hashes may refer to photos already attached to the order, which the arbiters can then fetch.
+   * The escrowed payment stays frozen until the dispute is resolved.
+   * @return newly created Dispute object for the given order ID.
+   */
+    #[payable]
+    pub fn open_dispute(&mut self, order_id: String, reason: String, evidence_hashes: Vec<String>) -> Dispute {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(env::predecessor_account_id() == order.customer_id, "You can only dispute your own order.");
+      require!(deposit_amount >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(order.status == OrderStatus::Delivered, "Order must have Delivered status.");
+      require!(!order.is_settled, "Order payment has already been settled.");
+      require!(env::block_timestamp() <= order.delivery_date_time.saturating_add(self.dispute_window), "Dispute window has closed.");
+      require!(self.disputes_lookup.get(&order_id).is_none(), "Order has already been disputed.");
+      require!(!reason.is_empty() && char_count(&reason) <= MAX_DISPUTE_REASON_LENGTH, "Dispute reason must be between 1 and 500 characters.");
+      require!(evidence_hashes.len() <= MAX_EVIDENCE_HASHES, "Dispute has too many evidence hashes.");
+      require!(evidence_hashes.iter().all(|hash| !hash.is_empty() && hash.len() <= MAX_EVIDENCE_HASH_LENGTH), "Evidence hash is invalid.");
+
+      let dispute = Dispute {
+        order_id: order_id.clone(),
+        customer_id: order.customer_id.clone(),
+        reason: reason.clone(),
+        evidence_hashes,
+        status: DisputeStatus::Open,
+        resolution: None,
+        resolution_note: None,
+        resolved_by: None,
+        created: env::block_timestamp(),
+        updated: env::block_timestamp(),
+      };
+
+      self.disputes_lookup.insert(&order_id, &dispute);
+
+      self.set_order_status(&mut order, OrderStatus::Disputed, Some(reason));
+
+      log!("opened dispute for order: {} successfully", &order_id);
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+      let surplus = u128::checked_sub(deposit_amount, final_storage_cost).unwrap();
+
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(surplus);
+        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      }
+
+      dispute
+    }
+
+    /**
+   * Only an admin or an arbiter can call this function.
+   * Refunds the customer in full or in part, or rejects the dispute and returns the order to Delivered.
+   * @return resolved Dispute object for the given order ID.
+   */
+    pub fn resolve_dispute(&mut self, order_id: String, resolution: DisputeResolution, resolution_note: Option<String>) -> Dispute {
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(self.check_is_admin(&caller) || self.check_is_arbiter(&caller), "Only admin or arbiter can resolve disputes.");
+
+      let mut dispute: Dispute = self.get_dispute_by_order_id(order_id.clone());
+
+      require!(dispute.status == DisputeStatus::Open, "Dispute has already been resolved.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      let refund: u128 = match resolution {
+        DisputeResolution::FullRefund => order.paid_in_yocto_near(),
+        DisputeResolution::PartialRefund(amount) => {
+          require!(amount.0 > 0 && amount.0 < order.paid_in_yocto_near(), "Partial refund must be less than the paid amount.");
+          amount.0
+        },
+        DisputeResolution::Rejected => 0,
+      };
+
+      dispute.status = DisputeStatus::Resolved;
+      dispute.resolution = Some(resolution);
+      dispute.resolution_note = resolution_note.clone();
+      dispute.resolved_by = Some(caller);
+      dispute.updated = env::block_timestamp();
+
+      self.disputes_lookup.insert(&order_id, &dispute);
+
+      if refund > 0 {
+        order.refunded_in_yocto_near = U128(refund);
+        order.is_settled = refund == order.paid_in_yocto_near();
+
+        self.set_order_status(&mut order, OrderStatus::Refunded, resolution_note);
+
+        self.transfer_order_funds(&order, &order.customer_id, refund, "refund");
+      } else {
+        self.set_order_status(&mut order, OrderStatus::Delivered, resolution_note);
+      }
+
+      log!("resolved dispute for order: {} successfully", &order_id);
+
+      dispute
+    }
+
+    /**
+   * Only the owning customer, an admin or an arbiter can fetch the dispute of the given order ID.
+   * @return Dispute object for the given order ID.
+   */
+    pub fn get_dispute_by_order_id(&self, order_id: String) -> Dispute  {
+      let dispute: Dispute = match self.disputes_lookup.get(&order_id) {
+        Some(dispute) => dispute,
+        None => env::panic_str("Dispute does not exist.")
+      };
+
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == dispute.customer_id || self.check_is_admin(&caller) || self.check_is_arbiter(&caller), "You can only fetch disputes of your own orders.");
+
+      dispute
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Releases the escrowed payment of a delivered order to the calling admin once it can no longer be disputed,
+   * or the part of a cancelled order's payment that was not refunded.
+   * @return settled Order object for the given order ID.
+   */
+    pub fn settle_order(&mut self, order_id: String) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can settle orders.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(
+        order.status == OrderStatus::Delivered || order.status == OrderStatus::Refunded || order.status == OrderStatus::Cancelled,
+        "Order must have Delivered, Refunded or Cancelled status."
+      );
+      require!(!order.is_settled, "Order payment has already been settled.");
+
+      let dispute: Option<Dispute> = self.disputes_lookup.get(&order_id);
+
+      require!(!dispute.as_ref().is_some_and(|dispute| dispute.status == DisputeStatus::Open), "Dispute is still open.");
+
+      let dispute_resolved: bool = dispute.is_some();
+
+      require!(dispute_resolved || order.status == OrderStatus::Cancelled || env::block_timestamp() > order.delivery_date_time.saturating_add(self.dispute_window), "Dispute window is still open.");
+
+      let payout: u128 = u128::checked_sub(order.paid_in_yocto_near(), order.refunded_in_yocto_near.0).unwrap();
+
+      order.is_settled = true;
+
+      self.save_order(&order);
+
+      log!("settled order: {} successfully", &order_id);
+
+      if payout > 0 {
+        self.transfer_order_funds(&order, &env::predecessor_account_id(), payout, "payout");
+      }
+
+      order
+    }
+}
@@ -0,0 +1,103 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::MAX_EVIDENCE_HASH_LENGTH;
+use crate::Order;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, log, require, Promise};
+
+pub const MAX_ORDER_EVIDENCE: usize = 20;
+
+/**
+ * all available evidence labels
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum EvidenceLabel {
+  PickupCondition = 1,
+  AfterWash,
+  DeliveredAtDoor,
+  Damage,
+  Other,
+}
+
+/**
+ * a content hash of a photo kept off-chain, such as an IPFS CID or a sha256 digest
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderEvidence {
+  pub hash: String,
+  pub label: EvidenceLabel,
+  pub added_by: AccountId,
+  pub timestamp: Timestamp,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * The owning customer or an admin attaches a photo hash to the given order ID.
+   * The caller pays for the storage of the evidence.
+   * @return updated Order object for the given order ID.
+   */
+    #[payable]
+    pub fn attach_order_evidence(&mut self, order_id: String, hash: String, label: EvidenceLabel) -> Order {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == order.customer_id || self.check_is_admin(&caller), "Only the customer of the order or an admin can attach evidence.");
+      require!(deposit_amount >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(!hash.is_empty() && hash.len() <= MAX_EVIDENCE_HASH_LENGTH, "Evidence hash is invalid.");
+      require!(order.evidence.len() < MAX_ORDER_EVIDENCE, "Order has too much evidence.");
+      require!(order.evidence.iter().all(|evidence| evidence.hash != hash), "Evidence has already been attached.");
+
+      order.evidence.push(OrderEvidence {
+        hash,
+        label,
+        added_by: caller,
+        timestamp: env::block_timestamp(),
+      });
+
+      self.save_order(&order);
+
+      log!("attached evidence to order: {} successfully", &order_id);
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+      let surplus = u128::checked_sub(deposit_amount, final_storage_cost).unwrap();
+
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(surplus);
+        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      }
+
+      order
+    }
+
+    /**
+   * Only the owning customer, an admin, or an arbiter once the order is disputed can fetch its evidence.
+   * @return evidence attached to the given order ID, oldest first.
+   */
+    pub fn get_order_evidence(&self, order_id: String) -> Vec<OrderEvidence>  {
+      if !self.check_order_exists(&order_id) {
+        env::panic_str("Order does not exist.")
+      }
+
+      let order: Order = self.orders_umap.get(&order_id).unwrap();
+      let caller: AccountId = env::predecessor_account_id();
+      let is_dispute_arbiter: bool = self.check_is_arbiter(&caller) && self.disputes_lookup.get(&order_id).is_some();
+
+      require!(caller == order.customer_id || self.check_is_admin(&caller) || is_dispute_arbiter, "You can only fetch evidence of your own order.");
+
+      order.evidence
+    }
+}
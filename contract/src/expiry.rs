@@ -0,0 +1,144 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderStatus};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, Timestamp, env, log, require};
+use near_sdk::json_types::U128;
+
+use std::ops::Bound;
+
+pub const DEFAULT_PICKUP_DEADLINE: u64 = 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_EXPIRED_ORDERS_PER_CALL: u64 = 20;
+
+/**
+ * expiry policy for orders that were never picked up
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExpiryPolicy {
+  pub pickup_deadline: u64,
+  pub bounty_in_yocto_near: U128,
+}
+
+impl Default for ExpiryPolicy {
+  fn default() -> Self {
+    ExpiryPolicy {
+      pickup_deadline: DEFAULT_PICKUP_DEADLINE,
+      bounty_in_yocto_near: U128(0),
+    }
+  }
+}
+
+impl ExpiryPolicy {
+  /**
+   * Checks if the given order is still waiting for pickup after the deadline.
+   * @return boolean for the given order.
+   */
+  pub fn is_overdue(&self, order: &Order, now: Timestamp) -> bool {
+    order.status == OrderStatus::PickupScheduled && now > order.pickup_window.end.saturating_add(self.pickup_deadline)
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Sets how long after the pickup window ends an order expires, in nanoseconds, and the bounty paid per expired order out of its refund.
+   * @return updated ExpiryPolicy object.
+   */
+    pub fn set_expiry_policy(&mut self, pickup_deadline: u64, bounty_in_yocto_near: U128) -> ExpiryPolicy {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can set expiry policy.");
+
+      self.expiry_policy = ExpiryPolicy {
+        pickup_deadline,
+        bounty_in_yocto_near,
+      };
+
+      log!("updated expiry policy successfully");
+
+      self.expiry_policy.clone()
+    }
+
+    /**
+   * Gets the current expiry policy.
+   * @return ExpiryPolicy object.
+   */
+    pub fn get_expiry_policy(&self) -> ExpiryPolicy {
+      self.expiry_policy.clone()
+    }
+
+    /**
+   * Anyone can call this function.
+   * Walks up to limit orders by pickup time, resuming where the last call stopped, and cancels the ones that were not picked up before the deadline.
+   * Their customers are refunded in full, less the storage cost of the change for NEAR orders and the expiry bounty, which is paid to the caller out of that refund.
+   * @return IDs of the expired orders.
+   */
+    pub fn expire_stale_orders(&mut self, limit: Option<u64>) -> Vec<String> {
+      let now: Timestamp = env::block_timestamp();
+      let cutoff: Timestamp = now.saturating_sub(self.expiry_policy.pickup_deadline);
+
+      let lower_bound: Bound<(Timestamp, String)> = match self.expiry_cursor.clone() {
+        Some(cursor) => Bound::Excluded(cursor),
+        None => Bound::Included((0, String::new())),
+      };
+
+      let pickup_orders: Vec<(Timestamp, String)> = self.pickup_orders_tmap
+        .range((lower_bound, Bound::Excluded((cutoff, String::new()))))
+        .map(|(key, _)| key)
+        .take(std::cmp::min(limit.unwrap_or(MAX_EXPIRED_ORDERS_PER_CALL), MAX_EXPIRED_ORDERS_PER_CALL) as usize)
+        .collect();
+
+      let mut order_ids: Vec<String> = Vec::new();
+
+      for key in pickup_orders {
+        let mut order: Order = self.orders_umap.get(&key.1).unwrap();
+
+        if order.status == OrderStatus::PickupScheduled {
+          if !self.expiry_policy.is_overdue(&order, now) {
+            break;
+          }
+
+          self.expire_order(&mut order);
+          order_ids.push(order.id);
+        }
+
+        self.expiry_cursor = Some(key);
+      }
+
+      log!("expired {} orders successfully", order_ids.len());
+
+      order_ids
+    }
+
+    /**
+   * Cancels the given overdue order, refunds its customer and pays the expiry bounty out of the refund.
+   */
+    fn expire_order(&mut self, order: &mut Order) {
+      let storage_used_before: u64 = env::storage_usage();
+
+      order.refunded_in_yocto_near = U128(order.paid_in_yocto_near());
+      order.is_settled = true;
+
+      self.set_order_status(order, OrderStatus::Cancelled, Some("Pickup deadline passed.".to_string()));
+
+      let final_storage_cost = self.charge_order_storage(order, storage_used_before);
+      let refund = self.cancellation_policy
+        .calculate_refund(OrderStatus::PickupScheduled, order.paid_in_yocto_near())
+        .unwrap()
+        .saturating_sub(final_storage_cost);
+      let bounty = std::cmp::min(self.expiry_policy.bounty_in_yocto_near.0, refund);
+
+      if refund > bounty {
+        self.transfer_order_funds(order, &order.customer_id, refund - bounty, "refund");
+      }
+
+      if bounty > 0 {
+        self.transfer_order_funds(order, &env::predecessor_account_id(), bounty, "bounty");
+      }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderStatus};
+use crate::Page;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, require};
+
+/**
+ * a single recorded status change of an order
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderStatusChange {
+  pub from_status: Option<OrderStatus>,
+  pub to_status: OrderStatus,
+  pub actor_id: AccountId,
+  pub reason: Option<String>,
+  pub timestamp: Timestamp,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Creates a status history list for the given order ID.
+   * @return newly created status history list for the given order ID.
+   */
+    fn create_order_history_list(&self, order_id: &String) -> Vector<OrderStatusChange> {
+      self.order_history_lookup.get(order_id).unwrap_or_else(|| {
+        let prefix: Vec<u8> = [
+                b"h".as_slice(),
+                &near_sdk::env::sha256_array(order_id.as_bytes()),
+            ]
+            .concat();
+
+          Vector::new(prefix)
+      })
+    }
+
+    /**
+   * Appends a status change made by the caller to the history of the given order ID.
+   */
+    pub(crate) fn record_order_status_change(&mut self, order_id: &String, from_status: Option<OrderStatus>, to_status: OrderStatus, reason: Option<String>) {
+      let change = OrderStatusChange {
+        from_status,
+        to_status,
+        actor_id: env::predecessor_account_id(),
+        reason,
+        timestamp: env::block_timestamp(),
+      };
+
+      let mut order_history: Vector<OrderStatusChange> = self.create_order_history_list(order_id);
+
+      order_history.push(&change);
+
+      self.order_history_lookup.insert(order_id, &order_history);
+    }
+
+    /**
+   * Only the owning customer or an admin can fetch the status history of the given order ID.
+   * @return a page of status changes for the given order ID, oldest first.
+   */
+    pub fn get_order_history(&self, order_id: String, from_index: Option<u64>, limit: Option<u64>) -> Page<OrderStatusChange>  {
+      if !self.check_order_exists(&order_id) {
+        env::panic_str("Order does not exist.")
+      }
+
+      let order: Order = self.orders_umap.get(&order_id).unwrap();
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == order.customer_id || self.check_is_admin(&caller), "You can only fetch history of your own order.");
+
+      let order_history: Vector<OrderStatusChange> = self.create_order_history_list(&order_id);
+
+      Page::from_vector(&order_history, from_index, limit)
+    }
+}
@@ -1,20 +1,68 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::{require, BorshStorageKey};
-use near_sdk::collections::{UnorderedMap, LookupMap, Vector};
+use near_sdk::collections::{UnorderedMap, UnorderedSet, LookupMap, LookupSet, TreeMap, Vector};
 use near_sdk::serde::{Serialize, Deserialize};
-use near_sdk::{near_bindgen, PanicOnDefault, AccountId, env, log};
+use near_sdk::{near_bindgen, PanicOnDefault, AccountId, Timestamp, env, log};
 
 pub mod admin;
 pub mod user;
 pub mod order;
+pub mod history;
+pub mod catalog;
+pub mod schedule;
+pub mod slot;
+pub mod cancellation;
+pub mod dispute;
+pub mod pagination;
+pub mod query;
+pub mod weighing;
+pub mod subscription;
+pub mod batch;
+pub mod expiry;
+pub mod message;
+pub mod evidence;
+pub mod claim;
+pub mod rating;
+pub mod review;
+pub mod token;
+pub mod payment;
+pub mod migration;
 
 pub use crate::admin::*;
 pub use crate::user::*;
 pub use crate::order::*;
+pub use crate::history::*;
+pub use crate::catalog::*;
+pub use crate::schedule::*;
+pub use crate::slot::*;
+pub use crate::cancellation::*;
+pub use crate::dispute::*;
+pub use crate::pagination::*;
+pub use crate::query::*;
+pub use crate::subscription::*;
+pub use crate::batch::*;
+pub use crate::expiry::*;
+pub use crate::message::*;
+pub use crate::evidence::*;
+pub use crate::claim::*;
+pub use crate::rating::*;
+pub use crate::review::*;
+pub use crate::token::*;
+pub use crate::payment::*;
+pub use crate::migration::*;
 
 pub const ONE_YOCTO_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
 pub const TGAS: u64 = 1_000_000_000_000;
 pub const NO_DEPOSIT: u128 = 0;
+pub const ORDER_ID_PREFIX: &str = "WK";
+
+/**
+ * Counts the characters of the given text; text limits are stated in characters rather than bytes.
+ * @return number of characters in the text.
+ */
+pub fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
 
 
 #[derive(Deserialize, Serialize, BorshStorageKey, BorshSerialize, Debug, Clone, Copy)]
@@ -23,7 +71,27 @@ pub enum StorageKeys {
     Admins,
     Users,
     Orders,
-    CustomerOrders
+    CustomerOrders,
+    OrderHistory,
+    Services,
+    PickupSlots,
+    Arbiters,
+    Disputes,
+    StatusOrders,
+    PickupOrders,
+    DeliveryOrders,
+    Subscriptions,
+    SubscriptionQueue,
+    OrderMessages,
+    Claims,
+    ServiceFeedbackStats,
+    OperatorFeedbackStats,
+    ReviewedOrders,
+    AcceptedTokens,
+    TokenStorageDeposits,
+    UnclaimedTokens,
+    MigratedOrders,
+    MigratedCustomerOrders,
 }
 
 #[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
@@ -43,7 +111,35 @@ pub struct Contract {
     pub admin_lookup: LookupMap<AccountId, Admin>,
     pub customers_umap: UnorderedMap<AccountId, User>,
     pub orders_umap: UnorderedMap<String, Order>,
-    pub customer_orders_lookup: LookupMap<AccountId, Vector<Order>>,
+    pub customer_orders_lookup: LookupMap<AccountId, Vector<String>>,
+    pub order_counter: u64,
+    pub order_history_lookup: LookupMap<String, Vector<OrderStatusChange>>,
+    pub services_umap: UnorderedMap<String, Service>,
+    pub pickup_slots_tmap: TreeMap<Timestamp, PickupSlot>,
+    pub cancellation_policy: CancellationPolicy,
+    pub dispute_window: u64,
+    pub arbiters_lookup: LookupSet<AccountId>,
+    pub disputes_lookup: LookupMap<String, Dispute>,
+    pub status_orders_lookup: LookupMap<OrderStatus, UnorderedSet<String>>,
+    pub pickup_orders_tmap: TreeMap<(Timestamp, String), ()>,
+    pub delivery_orders_tmap: TreeMap<(Timestamp, String), ()>,
+    pub subscriptions_lookup: LookupMap<AccountId, Subscription>,
+    pub subscription_queue_tmap: TreeMap<(Timestamp, AccountId), ()>,
+    pub expiry_policy: ExpiryPolicy,
+    pub expiry_cursor: Option<(Timestamp, String)>,
+    pub order_messages_lookup: LookupMap<String, Vector<OrderMessage>>,
+    pub claims_umap: UnorderedMap<String, Claim>,
+    pub claims_reserve_in_yocto_near: u128,
+    pub service_feedback_stats_lookup: LookupMap<String, FeedbackStats>,
+    pub operator_feedback_stats_lookup: LookupMap<AccountId, FeedbackStats>,
+    pub feedback_window: u64,
+    pub reviewed_orders_vec: Vector<String>,
+    pub accepted_tokens_umap: UnorderedMap<AccountId, AcceptedToken>,
+    pub token_storage_deposits_lookup: LookupMap<AccountId, u128>,
+    pub unclaimed_tokens_lookup: LookupMap<(AccountId, AccountId), u128>,
+    pub booking_deposit_in_yocto_near: u128,
+    pub legacy_orders: Option<LegacyOrders>,
+    pub available_pickup_slots_count: u64,
 }
 
 // Implement the contract structure
@@ -61,16 +157,52 @@ impl Contract {
 
         log!("Initializing contract...");
 
+        Self::new_state()
+    }
+
+    /**
+     * Creates the initial contract state with empty collections and default settings.
+     * @return Contract.
+    */
+    fn new_state() -> Self {
         Self {
             admin_lookup: LookupMap::new(StorageKeys::Admins),
             customers_umap: UnorderedMap::new(StorageKeys::Users),
             orders_umap: UnorderedMap::new(StorageKeys::Orders),
-            customer_orders_lookup: LookupMap::new(StorageKeys::CustomerOrders)
+            customer_orders_lookup: LookupMap::new(StorageKeys::CustomerOrders),
+            order_counter: 0,
+            order_history_lookup: LookupMap::new(StorageKeys::OrderHistory),
+            services_umap: UnorderedMap::new(StorageKeys::Services),
+            pickup_slots_tmap: TreeMap::new(StorageKeys::PickupSlots),
+            cancellation_policy: CancellationPolicy::default(),
+            dispute_window: DEFAULT_DISPUTE_WINDOW,
+            arbiters_lookup: LookupSet::new(StorageKeys::Arbiters),
+            disputes_lookup: LookupMap::new(StorageKeys::Disputes),
+            status_orders_lookup: LookupMap::new(StorageKeys::StatusOrders),
+            pickup_orders_tmap: TreeMap::new(StorageKeys::PickupOrders),
+            delivery_orders_tmap: TreeMap::new(StorageKeys::DeliveryOrders),
+            subscriptions_lookup: LookupMap::new(StorageKeys::Subscriptions),
+            subscription_queue_tmap: TreeMap::new(StorageKeys::SubscriptionQueue),
+            expiry_policy: ExpiryPolicy::default(),
+            expiry_cursor: None,
+            order_messages_lookup: LookupMap::new(StorageKeys::OrderMessages),
+            claims_umap: UnorderedMap::new(StorageKeys::Claims),
+            claims_reserve_in_yocto_near: 0,
+            service_feedback_stats_lookup: LookupMap::new(StorageKeys::ServiceFeedbackStats),
+            operator_feedback_stats_lookup: LookupMap::new(StorageKeys::OperatorFeedbackStats),
+            feedback_window: DEFAULT_FEEDBACK_WINDOW,
+            reviewed_orders_vec: Vector::new(StorageKeys::ReviewedOrders),
+            accepted_tokens_umap: UnorderedMap::new(StorageKeys::AcceptedTokens),
+            token_storage_deposits_lookup: LookupMap::new(StorageKeys::TokenStorageDeposits),
+            unclaimed_tokens_lookup: LookupMap::new(StorageKeys::UnclaimedTokens),
+            booking_deposit_in_yocto_near: DEFAULT_BOOKING_DEPOSIT,
+            legacy_orders: None,
+            available_pickup_slots_count: 0,
         }
     }
 
     /**
-     * Calculates storage cost.
+     * Calculates storage cost. Storage released since storage_used_before costs nothing.
      * @return storage cost
     */
     fn calculate_storage_cost(&self, storage_used_before: u64) -> u128 {
@@ -79,7 +211,7 @@ impl Contract {
         let storage_used_after: u64 = env::storage_usage();
         log!("storage_used_after: {} bytes", storage_used_after);
 
-        let payable_storage: u64 = storage_used_after - storage_used_before;
+        let payable_storage: u64 = storage_used_after.saturating_sub(storage_used_before);
         log!("payable_storage: {} bytes", payable_storage);
 
         let storage_cost_per_byte = env::storage_byte_cost();
@@ -91,13 +223,27 @@ impl Contract {
         final_storage_cost
     }
 
+    /**
+     * Calculates the cost of the storage released since storage_used_before.
+     * @return storage refund
+    */
+    fn calculate_storage_refund(&self, storage_used_before: u64) -> u128 {
+        let released_storage: u64 = storage_used_before.saturating_sub(env::storage_usage());
+        log!("released_storage: {} bytes", released_storage);
+
+        let storage_refund = u128::checked_mul(env::storage_byte_cost(), released_storage.into()).unwrap();
+        log!("storage_refund: {} yN", storage_refund);
+
+        storage_refund
+    }
+
     /**
      * Get details about the project
      * @return details about the project
     */
     pub fn get_project_info(&self) -> String {
         log!("executing: get_project_info");
-        return PROJECT_INFO.to_string();
+        PROJECT_INFO.to_string()
     }
 }
 
@@ -108,6 +254,94 @@ impl Contract {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use near_sdk::json_types::U128;
+    use near_sdk::{PromiseOrValue, PromiseResult, RuntimeFeesConfig, VMConfig};
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn set_context(predecessor: AccountId, deposit: u128) {
+        set_context_at(predecessor, deposit, 0);
+    }
+
+    fn set_context_at(predecessor: AccountId, deposit: u128, block_timestamp: u64) {
+        let mut builder = VMContextBuilder::new();
+        builder
+            .current_account_id(accounts(0))
+            .predecessor_account_id(predecessor)
+            .attached_deposit(deposit)
+            .block_timestamp(block_timestamp);
+        testing_env!(builder.build());
+    }
+
+    fn setup_customer(contract: &mut Contract, account_id: AccountId) {
+        set_context(account_id.clone(), ONE_YOCTO_NEAR);
+        contract.create_customer(
+            account_id.clone(),
+            account_id.to_string(),
+            "".to_string(),
+            "".to_string(),
+            "123 street, NY".to_string(),
+            "".to_string(),
+            "".to_string(),
+        );
+    }
+
+    // contract with admin accounts(2), customer accounts(1), a 1 NEAR per kg wash & fold service
+    // and unlimited pickup slots at hours 1-3 and 5-7
+    fn setup_contract() -> Contract {
+        set_context(accounts(0), 0);
+        let mut contract = Contract::init();
+        contract.create_admin(accounts(2));
+
+        set_context(accounts(2), 0);
+        contract.create_service("wash_and_fold".to_string(), "Wash & Fold".to_string(), U128(ONE_YOCTO_NEAR), U128(0));
+        contract.create_pickup_slot(window(1, 3), 0, 0);
+        contract.create_pickup_slot(window(5, 7), 0, 0);
+
+        setup_customer(&mut contract, accounts(1));
+        contract
+    }
+
+    fn new_item(service_id: &str, quantity: u32, weight_in_grams: u32) -> NewOrderItem {
+        NewOrderItem {
+            service_id: service_id.to_string(),
+            garment_type: GarmentType::Pants,
+            quantity,
+            weight_in_grams,
+            special_instructions: "".to_string(),
+        }
+    }
+
+    const HOUR: u64 = 3_600_000_000_000;
+
+    fn window(start_hour: u64, end_hour: u64) -> TimeWindow {
+        TimeWindow { start: start_hour * HOUR, end: end_hour * HOUR }
+    }
+
+    fn create_test_order(contract: &mut Contract, weight_in_grams: u32) -> Order {
+        set_context(accounts(1), 20 * ONE_YOCTO_NEAR);
+        contract.create_order(
+            accounts(1),
+            "2 pants".to_string(),
+            vec![new_item("wash_and_fold", 2, weight_in_grams)],
+            window(1, 3),
+            window(24, 26),
+            None,
+        )
+    }
+
+    fn deliver_test_order(contract: &mut Contract, order_id: &str) {
+        set_context(accounts(2), 0);
+        for status in [
+            OrderStatus::PickedUp,
+            OrderStatus::Washing,
+            OrderStatus::ReadyForDelivery,
+            OrderStatus::OutForDelivery,
+            OrderStatus::Delivered,
+        ] {
+            contract.update_order_status(order_id.to_string(), status, None);
+        }
+    }
 
     #[test]
     fn initializes() {
@@ -117,4 +351,1056 @@ mod tests {
             PROJECT_INFO.to_string()
         );
     }
+
+    #[test]
+    fn allocates_sequential_order_ids() {
+        let mut contract = setup_contract();
+
+        let first = create_test_order(&mut contract, 3500);
+        let second = create_test_order(&mut contract, 3500);
+
+        assert_eq!(first.id, "WK-1".to_string());
+        assert_eq!(second.id, "WK-2".to_string());
+        assert!(contract.check_order_exists(&second.id));
+    }
+
+    #[test]
+    fn records_order_status_history() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, Some("bag collected".to_string()));
+
+        let history = contract.get_order_history(order.id, None, None).items;
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].from_status, None);
+        assert_eq!(history[0].actor_id, accounts(1));
+        assert_eq!(history[1].from_status, Some(OrderStatus::PickupScheduled));
+        assert_eq!(history[1].to_status, OrderStatus::PickedUp);
+        assert_eq!(history[1].actor_id, accounts(2));
+        assert_eq!(history[1].reason, Some("bag collected".to_string()));
+    }
+
+    #[test]
+    fn threads_order_messages() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 1000);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.post_order_message(order.id.clone(), "Please use cold water.".to_string());
+        set_context(accounts(2), ONE_YOCTO_NEAR);
+        contract.post_order_message(order.id.clone(), "Noted.".to_string());
+
+        set_context(accounts(1), 0);
+        let page = contract.get_order_messages(order.id, Some(1), None);
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].sender_id, accounts(2));
+        assert_eq!(page.items[0].body, "Noted.".to_string());
+    }
+
+    #[test]
+    fn limits_order_messages_by_characters() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 1000);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        let message = contract.post_order_message(order.id, "é".repeat(MAX_MESSAGE_LENGTH));
+        assert_eq!(char_count(&message.body), MAX_MESSAGE_LENGTH);
+    }
+
+    #[test]
+    #[should_panic(expected = "Only the customer of the order or an admin can access its messages.")]
+    fn rejects_order_messages_from_other_accounts() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 1000);
+
+        set_context(accounts(3), ONE_YOCTO_NEAR);
+        contract.post_order_message(order.id, "hello".to_string());
+    }
+
+    #[test]
+    fn follows_order_status_transitions() {
+        assert_eq!(
+            OrderStatus::PickupScheduled.next_statuses(),
+            vec![OrderStatus::PickedUp, OrderStatus::Cancelled]
+        );
+        assert!(OrderStatus::OutForDelivery.can_transition_to(&OrderStatus::Delivered));
+        assert!(!OrderStatus::Delivered.can_transition_to(&OrderStatus::Cancelled));
+        assert!(OrderStatus::Refunded.next_statuses().is_empty());
+    }
+
+    #[test]
+    fn applies_batch_status_updates_per_entry() {
+        let mut contract = setup_contract();
+        let first = create_test_order(&mut contract, 1000);
+        let second = create_test_order(&mut contract, 1000);
+
+        set_context(accounts(2), 0);
+        let results = contract.update_order_statuses(vec![
+            (first.id.clone(), OrderStatus::PickedUp),
+            (second.id.clone(), OrderStatus::Delivered),
+            ("WK-9".to_string(), OrderStatus::PickedUp),
+            (second.id.clone(), OrderStatus::Cancelled),
+        ]);
+        assert_eq!(results.iter().map(|result| result.success).collect::<Vec<bool>>(), vec![true, false, false, true]);
+        assert_eq!(results[1].error, Some("Order can not move from PickupScheduled to Delivered.".to_string()));
+        assert_eq!(results[2].error, Some("Order does not exists.".to_string()));
+
+        set_context(accounts(1), 0);
+        assert_eq!(contract.get_order_by_id(first.id).status, OrderStatus::PickedUp);
+        assert_eq!(contract.get_order_by_id(second.id).status, OrderStatus::Cancelled);
+    }
+
+    #[test]
+    fn prices_orders_from_catalog() {
+        let mut contract = setup_contract();
+
+        set_context(accounts(2), 0);
+        contract.create_service("dry_clean".to_string(), "Dry Clean".to_string(), U128(0), U128(ONE_YOCTO_NEAR / 2));
+
+        let items = vec![
+            new_item("wash_and_fold", 0, 2500),
+            new_item("dry_clean", 3, 0),
+        ];
+        assert_eq!(contract.calculate_order_price(items).0, 4 * ONE_YOCTO_NEAR);
+
+        let order = create_test_order(&mut contract, 3500);
+        assert_eq!(order.price_in_yocto_near.0, 3 * ONE_YOCTO_NEAR + ONE_YOCTO_NEAR / 2);
+        assert_eq!(order.weight_in_grams, 3500);
+    }
+
+    #[test]
+    fn itemizes_order_lines() {
+        let mut contract = setup_contract();
+
+        set_context(accounts(2), 0);
+        contract.create_service("dry_clean".to_string(), "Dry Clean".to_string(), U128(0), U128(ONE_YOCTO_NEAR / 2));
+
+        set_context(accounts(1), 20 * ONE_YOCTO_NEAR);
+        let order = contract.create_order(
+            accounts(1),
+            "".to_string(),
+            vec![new_item("wash_and_fold", 5, 2000), new_item("dry_clean", 2, 1500)],
+            window(1, 3),
+            window(24, 26),
+            None,
+        );
+
+        assert_eq!(order.items.len(), 2);
+        assert_eq!(order.items[0].price_in_yocto_near.0, 2 * ONE_YOCTO_NEAR);
+        assert_eq!(order.items[1].price_in_yocto_near.0, ONE_YOCTO_NEAR);
+        assert_eq!(order.price_in_yocto_near.0, 3 * ONE_YOCTO_NEAR);
+        assert_eq!(order.weight_in_grams, 3500);
+        assert_eq!(contract.get_orders_by_customer_id(accounts(1), None, None).items[0].items, order.items);
+    }
+
+    #[test]
+    fn reschedules_order_windows() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+        assert_eq!(order.schedule_status, ScheduleStatus::Requested);
+        assert_eq!(order.pickup_date_time, 0);
+
+        set_context(accounts(2), 0);
+        let proposed = contract.propose_order_schedule(order.id.clone(), window(5, 7), window(30, 32));
+        assert_eq!(proposed.schedule_status, ScheduleStatus::Proposed);
+
+        set_context(accounts(1), 0);
+        let accepted = contract.accept_order_schedule(order.id.clone());
+        assert_eq!(accepted.schedule_status, ScheduleStatus::Confirmed);
+        assert_eq!(accepted.pickup_window, window(5, 7));
+        assert_eq!(accepted.delivery_window, window(30, 32));
+    }
+
+    #[test]
+    fn modifies_orders_before_pickup() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+        assert_eq!(order.address, "123 street, NY".to_string());
+
+        set_context(accounts(1), 0);
+        let modified = contract.modify_order(
+            order.id.clone(),
+            "1 pants".to_string(),
+            vec![new_item("wash_and_fold", 1, 2000)],
+            "456 avenue, NY".to_string(),
+            window(5, 7),
+            window(24, 26),
+        );
+        assert_eq!(modified.price_in_yocto_near.0, 2 * ONE_YOCTO_NEAR);
+        assert_eq!(modified.weight_in_grams, 2000);
+        assert_eq!(modified.address, "456 avenue, NY".to_string());
+        assert_eq!(modified.schedule_status, ScheduleStatus::Requested);
+        assert_eq!(contract.get_pickup_slot(window(1, 3).start).booked_orders, 0);
+        assert_eq!(contract.get_pickup_slot(window(5, 7).start).booked_weight_in_grams, 2000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposited amount must cover the price increase and storage cost.")]
+    fn rejects_unpaid_order_price_increase() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.modify_order(
+            order.id,
+            "4 pants".to_string(),
+            vec![new_item("wash_and_fold", 4, 7000)],
+            "123 street, NY".to_string(),
+            window(1, 3),
+            window(24, 26),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Delivery window must start after the pickup window ends.")]
+    fn rejects_delivery_before_pickup() {
+        let mut contract = setup_contract();
+
+        set_context(accounts(1), 20 * ONE_YOCTO_NEAR);
+        contract.create_order(
+            accounts(1),
+            "".to_string(),
+            vec![new_item("wash_and_fold", 2, 3500)],
+            window(5, 7),
+            window(6, 8),
+            None,
+        );
+    }
+
+    #[test]
+    fn deletes_past_pickup_slots_with_booked_orders() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 1000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context_at(accounts(2), 0, 3 * HOUR);
+        let deleted = contract.delete_pickup_slot(HOUR);
+        assert_eq!(deleted.booked_orders, 1);
+        assert!(contract.pickup_slots_tmap.get(&HOUR).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Pickup slot has booked orders.")]
+    fn keeps_upcoming_pickup_slots_with_booked_orders() {
+        let mut contract = setup_contract();
+        create_test_order(&mut contract, 1000);
+
+        set_context(accounts(2), 0);
+        contract.delete_pickup_slot(HOUR);
+    }
+
+    #[test]
+    fn caps_pickup_slot_capacity() {
+        let mut contract = setup_contract();
+
+        set_context(accounts(2), 0);
+        contract.update_pickup_slot(HOUR, 2, 5000);
+
+        create_test_order(&mut contract, 3000);
+
+        let available = contract.get_available_pickup_slots(None, None).items;
+        assert_eq!(available.len(), 2);
+        assert_eq!(available[0].remaining_orders, Some(1));
+        assert_eq!(available[0].remaining_weight_in_grams, Some(2000));
+        assert_eq!(available[1].remaining_orders, None);
+
+        let page = contract.get_available_pickup_slots(None, Some(1));
+        assert_eq!(page.next_cursor, Some(HOUR));
+        assert_eq!(page.total, 2);
+        let page = contract.get_available_pickup_slots(page.next_cursor, Some(1));
+        assert_eq!(page.items[0].window, window(5, 7));
+        assert_eq!(page.next_cursor, None);
+
+        let order = create_test_order(&mut contract, 2000);
+        let page = contract.get_available_pickup_slots(None, None);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.total, 1);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id, OrderStatus::Cancelled, None);
+        assert_eq!(contract.get_pickup_slot(HOUR).booked_orders, 1);
+        assert_eq!(contract.get_available_pickup_slots(None, None).total, 2);
+
+        contract.delete_pickup_slot(5 * HOUR);
+        assert_eq!(contract.get_available_pickup_slots(None, None).total, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Page limit must be greater than zero.")]
+    fn rejects_empty_cursor_pages() {
+        let contract = setup_contract();
+        contract.get_available_pickup_slots(None, Some(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "Pickup slot is full.")]
+    fn rejects_orders_for_full_slot() {
+        let mut contract = setup_contract();
+
+        set_context(accounts(2), 0);
+        contract.update_pickup_slot(HOUR, 0, 5000);
+
+        create_test_order(&mut contract, 3000);
+        create_test_order(&mut contract, 3000);
+    }
+
+    #[test]
+    fn refunds_cancellations_by_stage() {
+        let policy = CancellationPolicy { refund_percentage_after_pickup: 40 };
+        assert_eq!(policy.calculate_refund(OrderStatus::PickupScheduled, 10), Some(10));
+        assert_eq!(policy.calculate_refund(OrderStatus::PickedUp, 10), Some(4));
+        assert_eq!(policy.calculate_refund(OrderStatus::Washing, 10), None);
+    }
+
+    #[test]
+    fn expires_orders_past_pickup_deadline() {
+        let mut contract = setup_contract();
+        let stale = create_test_order(&mut contract, 1000);
+        let pending = create_test_order(&mut contract, 1000);
+
+        set_context(accounts(2), 0);
+        contract.propose_order_schedule(pending.id.clone(), window(5, 7), window(30, 32));
+        contract.set_expiry_policy(24 * HOUR, U128(ONE_YOCTO_NEAR / 100));
+
+        set_context_at(accounts(3), 0, 28 * HOUR);
+        assert_eq!(contract.expire_stale_orders(None), vec![stale.id.clone()]);
+        assert!(near_sdk::test_utils::get_logs().contains(&format!("Transferred bounty: {} yN to account_id: {}", ONE_YOCTO_NEAR / 100, accounts(3))));
+        assert!(contract.expire_stale_orders(None).is_empty());
+
+        set_context(accounts(1), 0);
+        assert_eq!(contract.get_order_by_id(stale.id).status, OrderStatus::Cancelled);
+        assert_eq!(contract.get_order_by_id(pending.id).status, OrderStatus::PickupScheduled);
+        assert_eq!(contract.get_pickup_slot(window(1, 3).start).booked_orders, 0);
+    }
+
+    #[test]
+    fn expires_orders_in_pickup_order_up_to_the_limit() {
+        let mut contract = setup_contract();
+        let first = create_test_order(&mut contract, 1000);
+        let delivered = create_test_order(&mut contract, 1000);
+        let second = create_test_order(&mut contract, 1000);
+        deliver_test_order(&mut contract, &delivered.id);
+
+        set_context_at(accounts(3), 0, 28 * HOUR);
+        assert_eq!(contract.expire_stale_orders(Some(2)), vec![first.id.clone()]);
+        assert_eq!(contract.expire_stale_orders(Some(2)), vec![second.id.clone()]);
+        assert!(contract.expire_stale_orders(Some(2)).is_empty());
+    }
+
+    #[test]
+    fn settles_the_fee_retained_on_cancellation_after_pickup() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+
+        set_context(accounts(1), 0);
+        let cancelled = contract.cancel_order(order.id.clone(), None);
+        assert_eq!(cancelled.refunded_in_yocto_near.0, 2 * ONE_YOCTO_NEAR);
+        assert!(!cancelled.is_settled);
+
+        set_context(accounts(2), 0);
+        let settled = contract.settle_order(order.id);
+        assert!(settled.is_settled);
+        assert!(near_sdk::test_utils::get_logs().contains(&format!("Transferred payout: {} yN to account_id: {}", 2 * ONE_YOCTO_NEAR, accounts(2))));
+    }
+
+    #[test]
+    #[should_panic(expected = "Order can no longer be cancelled.")]
+    fn rejects_customer_cancellation_once_washing() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+        contract.update_order_status(order.id.clone(), OrderStatus::Washing, None);
+
+        set_context(accounts(1), 0);
+        contract.cancel_order(order.id, None);
+    }
+
+    #[test]
+    fn reprices_orders_by_measured_weight() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+        let lighter = contract.record_order_weight(order.id.clone(), vec![3000]);
+        assert_eq!(lighter.status, OrderStatus::PickedUp);
+        assert_eq!(lighter.price_in_yocto_near.0, 3 * ONE_YOCTO_NEAR);
+        assert_eq!(lighter.amount_due_in_yocto_near.0, 0);
+
+        let heavier = contract.record_order_weight(order.id.clone(), vec![5000]);
+        assert_eq!(heavier.status, OrderStatus::AwaitingTopUp);
+        assert_eq!(heavier.weight_in_grams, 5000);
+        assert_eq!(heavier.amount_due_in_yocto_near.0, 2 * ONE_YOCTO_NEAR);
+
+        set_context(accounts(1), 3 * ONE_YOCTO_NEAR);
+        let topped_up = contract.top_up_order(order.id);
+        assert_eq!(topped_up.status, OrderStatus::PickedUp);
+        assert_eq!(topped_up.amount_due_in_yocto_near.0, 0);
+        assert_eq!(topped_up.paid_in_yocto_near(), 5 * ONE_YOCTO_NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Deposited amount must cover the amount due and storage cost.")]
+    fn rejects_top_ups_without_storage_cost() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 3500);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+        let heavier = contract.record_order_weight(order.id.clone(), vec![5000]);
+
+        set_context(accounts(1), heavier.amount_due_in_yocto_near.0);
+        contract.top_up_order(order.id);
+    }
+
+    #[test]
+    fn resolves_disputes_with_partial_refund() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(2), 0);
+        contract.add_arbiter(accounts(3));
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        let dispute = contract.open_dispute(order.id.clone(), "shirt torn".to_string(), vec!["bafybeigdyrzt".to_string()]);
+        assert_eq!(dispute.status, DisputeStatus::Open);
+
+        set_context(accounts(3), 0);
+        let resolved = contract.resolve_dispute(order.id.clone(), DisputeResolution::PartialRefund(U128(ONE_YOCTO_NEAR)), None);
+        assert_eq!(resolved.resolved_by, Some(accounts(3)));
+
+        set_context(accounts(2), 0);
+        let settled = contract.settle_order(order.id);
+        assert_eq!(settled.status, OrderStatus::Refunded);
+        assert_eq!(settled.refunded_in_yocto_near.0, ONE_YOCTO_NEAR);
+        assert!(settled.is_settled);
+    }
+
+    #[test]
+    fn keeps_delivery_time_when_dispute_is_rejected() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.open_dispute(order.id.clone(), "shirt torn".to_string(), vec!["bafybeigdyrzt".to_string()]);
+
+        set_context_at(accounts(2), 0, 2 * HOUR);
+        contract.resolve_dispute(order.id.clone(), DisputeResolution::Rejected, None);
+
+        set_context(accounts(1), 0);
+        let rejected = contract.get_order_by_id(order.id);
+        assert_eq!(rejected.status, OrderStatus::Delivered);
+        assert_eq!(rejected.delivery_date_time, 0);
+    }
+
+    #[test]
+    fn keeps_disputed_orders_out_of_admin_status_updates() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.open_dispute(order.id.clone(), "shirt torn".to_string(), vec!["bafybeigdyrzt".to_string()]);
+
+        set_context(accounts(2), 0);
+        let results = contract.update_order_statuses(vec![(order.id.clone(), OrderStatus::Delivered)]);
+        assert_eq!(results[0].error, Some("Disputed orders can only be moved by resolving the dispute.".to_string()));
+
+        assert!(contract.get_next_order_statuses(order.id.clone(), Some(true)).is_empty());
+        assert_eq!(contract.get_next_order_statuses(order.id.clone(), None), OrderStatus::Disputed.next_statuses());
+
+        set_context(accounts(1), 0);
+        assert_eq!(contract.get_order_by_id(order.id).status, OrderStatus::Disputed);
+    }
+
+    #[test]
+    fn offers_only_admin_status_updates_as_next_statuses() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        let delivered = create_test_order(&mut contract, 1000);
+        deliver_test_order(&mut contract, &delivered.id);
+
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+        assert_eq!(contract.get_next_order_statuses(order.id, Some(true)), vec![OrderStatus::Washing, OrderStatus::Cancelled]);
+        assert!(contract.get_next_order_statuses(delivered.id.clone(), Some(true)).is_empty());
+        assert_eq!(contract.get_next_order_statuses(delivered.id, None), vec![OrderStatus::Disputed]);
+    }
+
+    #[test]
+    fn shares_order_evidence_with_dispute_arbiters() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+
+        set_context(accounts(2), ONE_YOCTO_NEAR);
+        contract.attach_order_evidence(order.id.clone(), "bafybeipickup".to_string(), EvidenceLabel::PickupCondition);
+        contract.add_arbiter(accounts(3));
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        let with_evidence = contract.attach_order_evidence(order.id.clone(), "bafybeitorn".to_string(), EvidenceLabel::Damage);
+        assert_eq!(with_evidence.evidence.len(), 2);
+        assert_eq!(with_evidence.evidence[0].added_by, accounts(2));
+        contract.open_dispute(order.id.clone(), "shirt torn".to_string(), vec!["bafybeitorn".to_string()]);
+
+        set_context(accounts(3), 0);
+        let evidence = contract.get_order_evidence(order.id);
+        assert_eq!(evidence[1].label, EvidenceLabel::Damage);
+    }
+
+    #[test]
+    fn aggregates_order_ratings() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        let rated = contract.submit_feedback(order.id.clone(), OrderRating { wash_quality: 5, punctuality: 4, staff_behaviour: 5 }, "".to_string());
+        assert_eq!(rated.customer_feedback, CustomerFeedback::Excellent);
+        let rerated = contract.submit_feedback(order.id, OrderRating { wash_quality: 2, punctuality: 4, staff_behaviour: 3 }, "".to_string());
+        assert_eq!(rerated.customer_feedback, CustomerFeedback::Average);
+
+        let stats = contract.get_service_feedback_stats("wash_and_fold".to_string());
+        assert_eq!(stats.wash_quality.count, 1);
+        assert_eq!(stats.wash_quality.sum, 2);
+        assert_eq!(stats.wash_quality.distribution, [0, 1, 0, 0, 0]);
+        assert_eq!(contract.get_operator_feedback_stats(accounts(2)).punctuality.sum, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "Feedback can only be edited once.")]
+    fn limits_feedback_edits() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        let rating = OrderRating { wash_quality: 4, punctuality: 4, staff_behaviour: 4 };
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.submit_feedback(order.id.clone(), rating, "good".to_string());
+        contract.submit_feedback(order.id.clone(), rating, "very good".to_string());
+        contract.submit_feedback(order.id, rating, "excellent".to_string());
+    }
+
+    #[test]
+    fn limits_feedback_comments_by_characters() {
+        assert_valid_feedback_comment(&"é".repeat(MAX_FEEDBACK_COMMENT_LENGTH));
+    }
+
+    #[test]
+    #[should_panic(expected = "Feedback comment must be at most 500 characters.")]
+    fn rejects_feedback_comments_over_the_limit() {
+        assert_valid_feedback_comment(&"a".repeat(MAX_FEEDBACK_COMMENT_LENGTH + 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "Feedback window has closed.")]
+    fn rejects_feedback_after_window() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context_at(accounts(1), ONE_YOCTO_NEAR, DEFAULT_FEEDBACK_WINDOW + 1);
+        contract.submit_feedback(order.id, OrderRating { wash_quality: 4, punctuality: 4, staff_behaviour: 4 }, "".to_string());
+    }
+
+    #[test]
+    fn hides_feedback_comments_but_keeps_rating() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.submit_feedback(order.id.clone(), OrderRating { wash_quality: 1, punctuality: 1, staff_behaviour: 1 }, "abusive".to_string());
+
+        set_context(accounts(2), 0);
+        let hidden = contract.set_feedback_comment_hidden(order.id, true);
+        assert!(hidden.is_feedback_comment_hidden);
+        assert_eq!(contract.get_service_feedback_stats("wash_and_fold".to_string()).staff_behaviour.count, 1);
+    }
+
+    #[test]
+    fn lists_anonymized_reviews_with_replies() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        let rating = OrderRating { wash_quality: 5, punctuality: 5, staff_behaviour: 4 };
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.submit_feedback(order.id.clone(), rating, "spotless".to_string());
+        contract.submit_feedback(order.id.clone(), rating, "spotless, thanks".to_string());
+
+        set_context(accounts(2), 0);
+        contract.reply_to_review(order.id, "Thank you!".to_string());
+
+        let page = contract.get_reviews(None, None);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].comment, Some("spotless, thanks".to_string()));
+        assert_eq!(page.items[0].service_ids, vec!["wash_and_fold".to_string()]);
+        assert_eq!(page.items[0].reply.as_ref().unwrap().body, "Thank you!");
+    }
+
+    #[test]
+    fn pays_approved_claims_from_reserve() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(2), 5 * ONE_YOCTO_NEAR);
+        contract.fund_claims_reserve();
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        let claim = contract.file_claim(order.id.clone(), ClaimType::Damage, vec![0], "shirt torn".to_string(), U128(3 * ONE_YOCTO_NEAR));
+        assert_eq!(claim.status, ClaimStatus::Pending);
+
+        set_context(accounts(2), 0);
+        let approved = contract.approve_claim(order.id, U128(2 * ONE_YOCTO_NEAR), None);
+        assert_eq!(approved.status, ClaimStatus::Approved);
+        assert_eq!(contract.get_claims_reserve().0, 3 * ONE_YOCTO_NEAR);
+    }
+
+    #[test]
+    #[should_panic(expected = "Claims reserve is too low.")]
+    fn rejects_claims_beyond_reserve() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.file_claim(order.id.clone(), ClaimType::LostItem, vec![0], "pants missing".to_string(), U128(ONE_YOCTO_NEAR));
+
+        set_context(accounts(2), 0);
+        contract.approve_claim(order.id, U128(ONE_YOCTO_NEAR), None);
+    }
+
+    fn create_deferred_test_order(contract: &mut Contract, payment_type: PaymentType) -> Order {
+        set_context(accounts(1), 2 * ONE_YOCTO_NEAR);
+        contract.create_order(
+            accounts(1),
+            "2 pants".to_string(),
+            vec![new_item("wash_and_fold", 2, 4000)],
+            window(1, 3),
+            window(24, 26),
+            Some(payment_type),
+        )
+    }
+
+    #[test]
+    fn books_pay_after_weighing_orders_with_a_deposit() {
+        let mut contract = setup_contract();
+        let order = create_deferred_test_order(&mut contract, PaymentType::PayAfterWeighing);
+        assert_eq!(order.amount_due_in_yocto_near.0, 4 * ONE_YOCTO_NEAR - DEFAULT_BOOKING_DEPOSIT);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+        let weighed = contract.record_order_weight(order.id.clone(), vec![5000]);
+        assert_eq!(weighed.status, OrderStatus::AwaitingTopUp);
+
+        set_context(accounts(1), 5 * ONE_YOCTO_NEAR);
+        let paid = contract.top_up_order(order.id);
+        assert_eq!(paid.status, OrderStatus::PickedUp);
+        assert_eq!(paid.paid_in_yocto_near(), 5 * ONE_YOCTO_NEAR);
+    }
+
+    #[test]
+    fn holds_pay_after_weighing_orders_until_weighed_and_paid() {
+        let mut contract = setup_contract();
+        let order = create_deferred_test_order(&mut contract, PaymentType::PayAfterWeighing);
+
+        set_context(accounts(2), 0);
+        let results = contract.update_order_statuses(vec![
+            (order.id.clone(), OrderStatus::PickedUp),
+            (order.id.clone(), OrderStatus::Washing),
+        ]);
+        assert_eq!(results[1].error, Some("Order must be weighed and paid before processing.".to_string()));
+
+        contract.record_order_weight(order.id.clone(), vec![3000]);
+        set_context(accounts(1), 3 * ONE_YOCTO_NEAR);
+        contract.top_up_order(order.id.clone());
+
+        set_context(accounts(2), 0);
+        let washing = contract.update_order_status(order.id, OrderStatus::Washing, None);
+        assert_eq!(washing.status, OrderStatus::Washing);
+    }
+
+    #[test]
+    fn modifies_deferred_orders_against_the_booking_deposit() {
+        let mut contract = setup_contract();
+        let order = create_deferred_test_order(&mut contract, PaymentType::PayAfterWeighing);
+
+        set_context(accounts(1), 0);
+        let modified = contract.modify_order(
+            order.id.clone(),
+            "1 pants".to_string(),
+            vec![new_item("wash_and_fold", 1, 2000)],
+            "123 street, NY".to_string(),
+            window(1, 3),
+            window(24, 26),
+        );
+        assert_eq!(modified.paid_in_yocto_near(), DEFAULT_BOOKING_DEPOSIT);
+        assert_eq!(modified.amount_due_in_yocto_near.0, 2 * ONE_YOCTO_NEAR - DEFAULT_BOOKING_DEPOSIT);
+
+        let cancelled = contract.cancel_order(order.id, None);
+        assert_eq!(cancelled.status, OrderStatus::Cancelled);
+    }
+
+    #[test]
+    fn holds_unpaid_cash_on_delivery_orders_before_delivery() {
+        let mut contract = setup_contract();
+        let order = create_deferred_test_order(&mut contract, PaymentType::CashOnDelivery);
+
+        set_context(accounts(2), 0);
+        let results = contract.update_order_statuses(vec![
+            (order.id.clone(), OrderStatus::PickedUp),
+            (order.id.clone(), OrderStatus::Washing),
+            (order.id.clone(), OrderStatus::ReadyForDelivery),
+            (order.id.clone(), OrderStatus::OutForDelivery),
+            (order.id.clone(), OrderStatus::Delivered),
+        ]);
+        assert_eq!(results[4].error, Some("Order must be fully paid before delivery.".to_string()));
+
+        set_context(accounts(1), 0);
+        assert_eq!(contract.get_order_by_id(order.id).status, OrderStatus::OutForDelivery);
+    }
+
+    #[test]
+    fn settles_cash_on_delivery_orders_without_paying_out_cash() {
+        let mut contract = setup_contract();
+        let order = create_deferred_test_order(&mut contract, PaymentType::CashOnDelivery);
+
+        set_context(accounts(2), 0);
+        contract.update_order_status(order.id.clone(), OrderStatus::PickedUp, None);
+        contract.update_order_status(order.id.clone(), OrderStatus::Washing, None);
+        contract.update_order_status(order.id.clone(), OrderStatus::ReadyForDelivery, None);
+        contract.update_order_status(order.id.clone(), OrderStatus::OutForDelivery, None);
+        let paid = contract.confirm_cash_payment(order.id.clone(), U128(4 * ONE_YOCTO_NEAR - DEFAULT_BOOKING_DEPOSIT));
+        assert_eq!(paid.amount_due_in_yocto_near.0, 0);
+        assert_eq!(paid.paid_in_yocto_near(), DEFAULT_BOOKING_DEPOSIT);
+
+        let delivered = contract.update_order_status(order.id.clone(), OrderStatus::Delivered, None);
+        assert_eq!(delivered.status, OrderStatus::Delivered);
+
+        set_context_at(accounts(2), 0, DEFAULT_DISPUTE_WINDOW + 1);
+        let settled = contract.settle_order(order.id);
+        assert!(settled.is_settled);
+        assert!(near_sdk::test_utils::get_logs().contains(&format!("Transferred payout: {} yN to account_id: {}", DEFAULT_BOOKING_DEPOSIT, accounts(2))));
+    }
+
+    fn create_token_order_message() -> String {
+        near_sdk::serde_json::json!({
+            "CreateOrder": {
+                "description": "2 pants",
+                "items": [{
+                    "service_id": "wash_and_fold",
+                    "garment_type": "Pants",
+                    "quantity": 2,
+                    "weight_in_grams": 4000,
+                    "special_instructions": "",
+                }],
+                "pickup_window": window(1, 3),
+                "delivery_window": window(24, 26),
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn creates_orders_paid_in_accepted_tokens() {
+        let mut contract = setup_contract();
+        set_context(accounts(2), 0);
+        contract.add_accepted_token(accounts(4), "USDC".to_string(), U128(2_000_000));
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.deposit_token_storage();
+
+        set_context(accounts(4), 0);
+        let unused = match contract.ft_on_transfer(accounts(1), U128(10_000_000), create_token_order_message()) {
+            PromiseOrValue::Value(unused) => unused,
+            PromiseOrValue::Promise(_) => panic!("expected a value"),
+        };
+        assert_eq!(unused.0, 2_000_000);
+
+        let order = contract.orders_umap.get(&"WK-1".to_string()).unwrap();
+        assert_eq!(order.payment_type, PaymentType::FungibleToken);
+        assert_eq!(order.payment_token.unwrap().token_id, accounts(4));
+        assert_eq!(order.price_in_yocto_near.0, 4 * ONE_YOCTO_NEAR);
+        assert!(contract.get_token_storage_balance(accounts(1)).0 < ONE_YOCTO_NEAR);
+    }
+
+    #[test]
+    fn refunds_cancelled_token_orders_in_full() {
+        let mut contract = setup_contract();
+        set_context(accounts(2), 0);
+        contract.add_accepted_token(accounts(4), "USDC".to_string(), U128(2_000_000));
+
+        set_context(accounts(1), ONE_YOCTO_NEAR);
+        contract.deposit_token_storage();
+
+        set_context(accounts(4), 0);
+        contract.ft_on_transfer(accounts(1), U128(8_000_000), create_token_order_message());
+        let storage_balance = contract.get_token_storage_balance(accounts(1)).0;
+
+        set_context(accounts(1), 0);
+        contract.cancel_order("WK-1".to_string(), None);
+        assert!(near_sdk::test_utils::get_logs().contains(&format!("Transferred surplus: 8000000 USDC to account_id: {}", accounts(1))));
+        assert!(contract.get_token_storage_balance(accounts(1)).0 < storage_balance);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token storage balance must cover the storage of the order.")]
+    fn rejects_token_orders_without_storage_balance() {
+        let mut contract = setup_contract();
+        set_context(accounts(2), 0);
+        contract.add_accepted_token(accounts(4), "USDC".to_string(), U128(2_000_000));
+
+        set_context(accounts(4), 0);
+        contract.ft_on_transfer(accounts(1), U128(10_000_000), create_token_order_message());
+    }
+
+    #[test]
+    fn records_failed_token_transfers_for_claiming() {
+        let mut contract = setup_contract();
+
+        let mut builder = VMContextBuilder::new();
+        builder.current_account_id(accounts(0)).predecessor_account_id(accounts(0));
+        testing_env!(builder.build(), VMConfig::test(), RuntimeFeesConfig::test(), Default::default(), vec![PromiseResult::Failed]);
+        assert!(!contract.resolve_token_transfer(accounts(1), accounts(4), U128(500)));
+        assert_eq!(contract.get_unclaimed_tokens(accounts(1), accounts(4)).0, 500);
+
+        set_context(accounts(1), 0);
+        assert_eq!(contract.claim_tokens(accounts(4)).0, 500);
+        assert_eq!(contract.get_unclaimed_tokens(accounts(1), accounts(4)).0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Transferred amount must cover the order price.")]
+    fn rejects_token_payments_below_price() {
+        let mut contract = setup_contract();
+        set_context(accounts(2), 0);
+        contract.add_accepted_token(accounts(4), "USDC".to_string(), U128(2_000_000));
+
+        set_context(accounts(4), 0);
+        contract.ft_on_transfer(accounts(1), U128(7_999_999), create_token_order_message());
+    }
+
+    #[test]
+    #[should_panic(expected = "Dispute window is still open.")]
+    fn holds_payment_during_dispute_window() {
+        let mut contract = setup_contract();
+        let order = create_test_order(&mut contract, 4000);
+        deliver_test_order(&mut contract, &order.id);
+
+        contract.settle_order(order.id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Dispute window is still open.")]
+    fn keeps_unbounded_dispute_windows_open() {
+        let mut contract = setup_contract();
+        let disputed = create_test_order(&mut contract, 4000);
+        let held = create_test_order(&mut contract, 1000);
+
+        set_context(accounts(2), 0);
+        contract.set_dispute_window(u64::MAX);
+        for order_id in [&disputed.id, &held.id] {
+            for status in [OrderStatus::PickedUp, OrderStatus::Washing, OrderStatus::ReadyForDelivery, OrderStatus::OutForDelivery] {
+                contract.update_order_status(order_id.clone(), status, None);
+            }
+        }
+        set_context_at(accounts(2), 0, HOUR);
+        contract.update_order_status(disputed.id.clone(), OrderStatus::Delivered, None);
+        contract.update_order_status(held.id.clone(), OrderStatus::Delivered, None);
+
+        set_context_at(accounts(1), ONE_YOCTO_NEAR, 2 * HOUR);
+        let dispute = contract.open_dispute(disputed.id.clone(), "shirt torn".to_string(), vec![]);
+        assert_eq!(dispute.order_id, disputed.id);
+
+        set_context_at(accounts(2), 0, u64::MAX);
+        contract.settle_order(held.id);
+    }
+
+    #[test]
+    fn paginates_order_lists() {
+        let mut contract = setup_contract();
+        for _ in 0..5 {
+            create_test_order(&mut contract, 1000);
+        }
+
+        set_context(accounts(2), 0);
+        let page = contract.get_order_list(Some(3), Some(10));
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].id, "WK-4".to_string());
+
+        set_context(accounts(1), 0);
+        let page = contract.get_orders_by_customer_id(accounts(1), None, Some(2));
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[1].id, "WK-2".to_string());
+    }
+
+    #[test]
+    fn processes_due_subscriptions() {
+        let mut contract = setup_contract();
+
+        set_context(accounts(2), 0);
+        contract.create_pickup_slot(window(25, 27), 0, 0);
+
+        set_context(accounts(1), 4 * ONE_YOCTO_NEAR);
+        let subscription = contract.create_subscription(
+            "weekly wash".to_string(),
+            vec![new_item("wash_and_fold", 2, 2000)],
+            24 * HOUR,
+            window(1, 3),
+            window(10, 12),
+        );
+        assert!(subscription.balance_in_yocto_near.0 > 3 * ONE_YOCTO_NEAR);
+
+        set_context(accounts(3), 0);
+        let order_ids = contract.process_due_subscriptions(None);
+        assert_eq!(order_ids, vec!["WK-1".to_string()]);
+        assert!(contract.process_due_subscriptions(None).is_empty());
+
+        set_context_at(accounts(3), 0, 2 * HOUR);
+        assert!(contract.process_due_subscriptions(None).is_empty());
+
+        set_context(accounts(1), 0);
+        let subscription = contract.get_subscription(accounts(1));
+        assert_eq!(subscription.status, SubscriptionStatus::Paused);
+        assert_eq!(subscription.pickup_window, window(25, 27));
+        assert_eq!(contract.get_orders_by_customer_id(accounts(1), None, None).total, 1);
+    }
+
+    #[test]
+    fn queries_orders_by_status_and_date() {
+        let mut contract = setup_contract();
+        for _ in 0..3 {
+            create_test_order(&mut contract, 1000);
+        }
+
+        set_context(accounts(2), 0);
+        contract.update_order_status("WK-1".to_string(), OrderStatus::PickedUp, None);
+        contract.propose_order_schedule("WK-3".to_string(), window(5, 7), window(30, 32));
+
+        let page = contract.get_orders_by_status(OrderStatus::PickupScheduled, None, None);
+        assert_eq!(page.total, 2);
+        let page = contract.get_orders_by_status(OrderStatus::PickedUp, None, None);
+        assert_eq!(page.total, 1);
+        assert_eq!(page.items[0].id, "WK-1".to_string());
+
+        let page = contract.get_orders_by_date_range(OrderDateField::Pickup, HOUR, 5 * HOUR, None, Some(1));
+        assert_eq!(page.items[0].id, "WK-1".to_string());
+        assert_eq!(page.next_cursor, Some((HOUR, "WK-1".to_string())));
+        assert_eq!(page.total, 3);
+        let page = contract.get_orders_by_date_range(OrderDateField::Pickup, HOUR, 5 * HOUR, page.next_cursor, Some(1));
+        assert_eq!(page.items[0].id, "WK-2".to_string());
+        assert_eq!(page.next_cursor, None);
+        let page = contract.get_orders_by_date_range(OrderDateField::Delivery, 30 * HOUR, 31 * HOUR, None, None);
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, "WK-3".to_string());
+
+        let page = contract.get_orders_by_customer_id(accounts(1), Some(1), Some(1));
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0].id, "WK-2".to_string());
+    }
+
+    #[test]
+    fn migrates_legacy_state() {
+        set_context(accounts(0), 0);
+        let mut legacy = LegacyContract {
+            admin_lookup: LookupMap::new(StorageKeys::Admins),
+            customers_umap: UnorderedMap::new(StorageKeys::Users),
+            orders_umap: UnorderedMap::new(StorageKeys::Orders),
+            customer_orders_lookup: LookupMap::new(StorageKeys::CustomerOrders),
+        };
+        legacy.customers_umap.insert(&accounts(1), &User {
+            id: accounts(1),
+            name: "bob".to_string(),
+            full_address: "123 street, NY".to_string(),
+            landmark: "".to_string(),
+            google_plus_code_address: "".to_string(),
+            phone: "".to_string(),
+            email: "".to_string(),
+            role: UserRole::Customer,
+            created: 0,
+            updated: 0,
+        });
+
+        let legacy_order = LegacyOrder {
+            id: "fghjk".to_string(),
+            customer_id: accounts(1),
+            description: "2 pants".to_string(),
+            weight_in_grams: 3500,
+            price_in_yocto_near: U128(3 * ONE_YOCTO_NEAR),
+            payment_type: LegacyPaymentType::Prepaid,
+            status: LegacyOrderStatus::Delivered,
+            customer_feedback: LegacyCustomerFeedback::Good,
+            customer_feedback_comment: "very good service.".to_string(),
+            pickup_date_time: 10,
+            delivery_date_time: 20,
+        };
+        let open_legacy_order = LegacyOrder {
+            id: "WK-1".to_string(),
+            status: LegacyOrderStatus::Confirmed,
+            customer_feedback: LegacyCustomerFeedback::None,
+            customer_feedback_comment: "".to_string(),
+            delivery_date_time: 0,
+            ..legacy_order.clone()
+        };
+        legacy.orders_umap.insert(&open_legacy_order.id, &open_legacy_order);
+        legacy.orders_umap.insert(&legacy_order.id, &legacy_order);
+        legacy.admin_lookup.insert(&accounts(2), &Admin {
+            id: accounts(2),
+            role: UserRole::Admin,
+            created: 0,
+            updated: 0,
+        });
+
+        let prefix: Vec<u8> = [b"s".as_slice(), &env::sha256_array(accounts(1).as_bytes())].concat();
+        let mut legacy_customer_orders: Vector<LegacyOrder> = Vector::new(prefix.clone());
+        legacy_customer_orders.push(&legacy_order);
+        legacy_customer_orders.push(&open_legacy_order);
+        legacy.customer_orders_lookup.insert(&accounts(1), &legacy_customer_orders);
+        env::state_write(&legacy);
+
+        let mut contract = Contract::migrate();
+
+        set_context_at(accounts(2), 0, 100 * HOUR);
+        assert_eq!(contract.migrate_orders(Some(1)), 3);
+
+        set_context(accounts(2), 0);
+        contract.create_service("wash_and_fold".to_string(), "Wash & Fold".to_string(), U128(ONE_YOCTO_NEAR), U128(0));
+        contract.create_pickup_slot(window(1, 3), 0, 0);
+        assert_eq!(create_test_order(&mut contract, 1000).id, "WK-2".to_string());
+
+        set_context_at(accounts(2), 0, 100 * HOUR);
+        assert_eq!(contract.migrate_orders(None), 0);
+        assert!(contract.legacy_orders.is_none());
+
+        set_context_at(accounts(3), 0, 101 * HOUR);
+        assert_eq!(contract.expire_stale_orders(None), vec!["WK-2".to_string()]);
+
+        set_context(accounts(1), 0);
+        let page = contract.get_orders_by_customer_id(accounts(1), None, None);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.items[0].id, "fghjk".to_string());
+        assert_eq!(page.items[0].status, OrderStatus::Delivered);
+        assert_eq!(page.items[0].customer_feedback, CustomerFeedback::Good);
+        assert_eq!(page.items[0].address, "123 street, NY".to_string());
+        assert_eq!(page.items[0].items[0].price_in_yocto_near.0, 3 * ONE_YOCTO_NEAR);
+        assert!(page.items[0].is_settled);
+        assert_eq!(page.items[1].id, "WK-2".to_string());
+        assert_eq!(page.items[2].id, "WK-1".to_string());
+        assert_eq!(page.items[2].status, OrderStatus::PickupScheduled);
+        assert_eq!(page.items[2].pickup_window.start, 100 * HOUR);
+        assert!(!env::storage_has_key(&[prefix.clone(), 0u64.to_le_bytes().to_vec()].concat()));
+        assert!(!env::storage_has_key(&[prefix, 1u64.to_le_bytes().to_vec()].concat()));
+    }
 }
@@ -0,0 +1,115 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::char_count;
+use crate::Order;
+use crate::Page;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, log, require, Promise};
+
+pub const MAX_MESSAGE_LENGTH: usize = 1000;
+
+/**
+ * a message posted to the thread of an order
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderMessage {
+  pub sender_id: AccountId,
+  pub body: String,
+  pub timestamp: Timestamp,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Creates a message thread for the given order ID.
+   * @return newly created message thread for the given order ID.
+   */
+    fn create_order_message_list(&self, order_id: &String) -> Vector<OrderMessage> {
+      self.order_messages_lookup.get(order_id).unwrap_or_else(|| {
+        let prefix: Vec<u8> = [
+                b"m".as_slice(),
+                &near_sdk::env::sha256_array(order_id.as_bytes()),
+            ]
+            .concat();
+
+          Vector::new(prefix)
+      })
+    }
+
+    /**
+   * Checks that the caller is the owning customer of the given order or an admin.
+   * @return Order object for the given order ID.
+   */
+    fn get_order_for_thread_party(&self, order_id: &String) -> Order {
+      if !self.check_order_exists(order_id) {
+        env::panic_str("Order does not exist.")
+      }
+
+      let order: Order = self.orders_umap.get(order_id).unwrap();
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == order.customer_id || self.check_is_admin(&caller), "Only the customer of the order or an admin can access its messages.");
+
+      order
+    }
+
+    /**
+   * The owning customer or an admin appends a message to the thread of the given order ID.
+   * The sender pays for the storage of the message.
+   * @return newly posted OrderMessage object.
+   */
+    #[payable]
+    pub fn post_order_message(&mut self, order_id: String, body: String) -> OrderMessage {
+      self.get_order_for_thread_party(&order_id);
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      require!(deposit_amount >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(!body.is_empty() && char_count(&body) <= MAX_MESSAGE_LENGTH, "Message must be between 1 and 1000 characters.");
+
+      let message = OrderMessage {
+        sender_id: env::predecessor_account_id(),
+        body,
+        timestamp: env::block_timestamp(),
+      };
+
+      let mut order_messages: Vector<OrderMessage> = self.create_order_message_list(&order_id);
+
+      order_messages.push(&message);
+
+      self.order_messages_lookup.insert(&order_id, &order_messages);
+
+      log!("posted message to order: {} successfully", &order_id);
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+      let surplus = u128::checked_sub(deposit_amount, final_storage_cost).unwrap();
+
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(surplus);
+        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      }
+
+      message
+    }
+
+    /**
+   * Only the owning customer or an admin can read the message thread of the given order ID.
+   * @return a page of messages for the given order ID, oldest first.
+   */
+    pub fn get_order_messages(&self, order_id: String, from_index: Option<u64>, limit: Option<u64>) -> Page<OrderMessage>  {
+      self.get_order_for_thread_party(&order_id);
+
+      let order_messages: Vector<OrderMessage> = self.create_order_message_list(&order_id);
+
+      Page::from_vector(&order_messages, from_index, limit)
+    }
+}
@@ -0,0 +1,295 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Admin, User, StorageKeys};
+use crate::{CustomerFeedback, GarmentType, Order, OrderItem, OrderStatus, PaymentType, ScheduleStatus, TimeWindow};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::{UnorderedMap, LookupMap, Vector};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, log, require};
+use near_sdk::json_types::U128;
+
+pub const LEGACY_SERVICE_ID: &str = "legacy";
+pub const LEGACY_PICKUP_WINDOW: u64 = 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_MIGRATED_ORDERS_PER_CALL: u64 = 50;
+
+/**
+ * order statuses of the first deployed contract version
+ **/
+#[derive(BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+pub enum LegacyOrderStatus {
+  Confirmed = 1,
+  InProgress,
+  Delivered,
+  Cancelled,
+}
+
+/**
+ * payment types of the first deployed contract version
+ **/
+#[derive(BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+pub enum LegacyPaymentType {
+  Prepaid = 1,
+}
+
+/**
+ * customer feedback responses of the first deployed contract version
+ **/
+#[derive(BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+pub enum LegacyCustomerFeedback {
+  None = 1,
+  Excellent,
+  Good,
+  Average,
+  Bad,
+  Worst,
+}
+
+/**
+ * order layout of the first deployed contract version
+ **/
+#[derive(BorshDeserialize, BorshSerialize, Debug, Clone)]
+pub struct LegacyOrder {
+  pub id: String,
+  pub customer_id: AccountId,
+  pub description: String,
+  pub weight_in_grams: u32,
+  pub price_in_yocto_near: U128,
+  pub payment_type: LegacyPaymentType,
+  pub status: LegacyOrderStatus,
+  pub customer_feedback: LegacyCustomerFeedback,
+  pub customer_feedback_comment: String,
+  pub pickup_date_time: Timestamp,
+  pub delivery_date_time: Timestamp,
+}
+
+/**
+ * contract layout of the first deployed contract version, which kept full order copies per customer
+ **/
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct LegacyContract {
+  pub admin_lookup: LookupMap<AccountId, Admin>,
+  pub customers_umap: UnorderedMap<AccountId, User>,
+  pub orders_umap: UnorderedMap<String, LegacyOrder>,
+  pub customer_orders_lookup: LookupMap<AccountId, Vector<LegacyOrder>>,
+}
+
+/**
+ * legacy orders that are still waiting to be moved to the current layout,
+ * with the position of the customer and order copy the next batch resumes at
+ **/
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct LegacyOrders {
+  pub orders_umap: UnorderedMap<String, LegacyOrder>,
+  pub customer_orders_lookup: LookupMap<AccountId, Vector<LegacyOrder>>,
+  pub customer_index: u64,
+  pub order_index: u64,
+  pub copies_left: u64,
+}
+
+impl LegacyOrder {
+  /**
+   * Converts the legacy order into the current layout as a single line item.
+   * Delivered orders were paid out on delivery, so their payment counts as settled.
+   * Legacy orders had no address of their own, so the given customer address is used.
+   * Orders still waiting for pickup get a pickup window starting at the given migration time, so they do not expire at once.
+   * @return Order object for this legacy order.
+   */
+  pub fn into_order(self, address: String, migrated_at: Timestamp) -> Order {
+    let status: OrderStatus = match self.status {
+      LegacyOrderStatus::Confirmed => OrderStatus::PickupScheduled,
+      LegacyOrderStatus::InProgress => OrderStatus::Washing,
+      LegacyOrderStatus::Delivered => OrderStatus::Delivered,
+      LegacyOrderStatus::Cancelled => OrderStatus::Cancelled,
+    };
+
+    let customer_feedback: CustomerFeedback = match self.customer_feedback {
+      LegacyCustomerFeedback::None => CustomerFeedback::None,
+      LegacyCustomerFeedback::Excellent => CustomerFeedback::Excellent,
+      LegacyCustomerFeedback::Good => CustomerFeedback::Good,
+      LegacyCustomerFeedback::Average => CustomerFeedback::Average,
+      LegacyCustomerFeedback::Bad => CustomerFeedback::Bad,
+      LegacyCustomerFeedback::Worst => CustomerFeedback::Worst,
+    };
+
+    let payment_type: PaymentType = match self.payment_type {
+      LegacyPaymentType::Prepaid => PaymentType::Prepaid,
+    };
+
+    let pickup_window: TimeWindow = match status {
+      OrderStatus::PickupScheduled => TimeWindow { start: migrated_at, end: migrated_at + LEGACY_PICKUP_WINDOW },
+      _ => TimeWindow { start: self.pickup_date_time, end: self.pickup_date_time },
+    };
+
+    Order {
+      id: self.id,
+      customer_id: self.customer_id,
+      description: self.description.clone(),
+      address,
+      items: vec![OrderItem {
+        service_id: LEGACY_SERVICE_ID.to_string(),
+        garment_type: GarmentType::Other,
+        quantity: 0,
+        weight_in_grams: self.weight_in_grams,
+        special_instructions: self.description,
+        price_in_yocto_near: self.price_in_yocto_near,
+      }],
+      weight_in_grams: self.weight_in_grams,
+      price_in_yocto_near: self.price_in_yocto_near,
+      refunded_in_yocto_near: U128(0),
+      amount_due_in_yocto_near: U128(0),
+      cash_paid_in_yocto_near: U128(0),
+      is_settled: status == OrderStatus::Delivered || status == OrderStatus::Cancelled,
+      payment_type,
+      payment_token: None,
+      status,
+      customer_feedback,
+      customer_feedback_comment: self.customer_feedback_comment,
+      rating: None,
+      feedback_edit_count: 0,
+      is_feedback_comment_hidden: false,
+      feedback_date_time: 0,
+      feedback_reply: None,
+      pickup_window,
+      delivery_window: TimeWindow { start: self.delivery_date_time, end: self.delivery_date_time },
+      schedule_status: ScheduleStatus::Confirmed,
+      pickup_date_time: self.pickup_date_time,
+      delivery_date_time: self.delivery_date_time,
+      delivered_by: None,
+      evidence: Vec::new(),
+    }
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+     * Migrates the state of the first deployed contract version. Only contract can call this function.
+     * Orders keep their legacy layout until they are moved in batches with migrate_orders.
+     * @return migrated Contract.
+    */
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let legacy: LegacyContract = env::state_read().expect("Contract is not initialized.");
+
+        log!("Migrating contract...");
+
+        let contract = Self {
+            admin_lookup: legacy.admin_lookup,
+            customers_umap: legacy.customers_umap,
+            orders_umap: UnorderedMap::new(StorageKeys::MigratedOrders),
+            customer_orders_lookup: LookupMap::new(StorageKeys::MigratedCustomerOrders),
+            legacy_orders: Some(LegacyOrders {
+                copies_left: legacy.orders_umap.len(),
+                orders_umap: legacy.orders_umap,
+                customer_orders_lookup: legacy.customer_orders_lookup,
+                customer_index: 0,
+                order_index: 0,
+            }),
+            ..Self::new_state()
+        };
+
+        log!("{} orders left to migrate", contract.legacy_orders.as_ref().unwrap().orders_umap.len());
+
+        contract
+    }
+
+    /**
+     * Only the admin can call this function.
+     * Walks the legacy order copies of each customer in their original order, resuming where the previous batch stopped.
+     * Each step either moves the order of the next copy to the current layout or deletes a copy already walked,
+     * so a batch does at most limit steps. Orders without a copy are moved once every customer has been walked.
+     * @return number of legacy orders and order copies left to migrate.
+    */
+    pub fn migrate_orders(&mut self, limit: Option<u64>) -> u64 {
+        require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can migrate orders.");
+
+        let mut legacy: LegacyOrders = match self.legacy_orders.take() {
+            Some(legacy) => legacy,
+            None => env::panic_str("No orders left to migrate."),
+        };
+
+        let limit: u64 = std::cmp::min(limit.unwrap_or(MAX_MIGRATED_ORDERS_PER_CALL), MAX_MIGRATED_ORDERS_PER_CALL);
+        let mut migrated: u64 = 0;
+
+        for _ in 0..limit {
+            let customer_id: Option<AccountId> = self.customers_umap.keys_as_vector().get(legacy.customer_index);
+
+            let legacy_order: Option<LegacyOrder> = match customer_id {
+                Some(customer_id) => Self::next_legacy_order(&mut legacy, &customer_id),
+                None => match legacy.orders_umap.keys_as_vector().get(0) {
+                    Some(order_id) => legacy.orders_umap.remove(&order_id),
+                    None => break,
+                },
+            };
+
+            if let Some(legacy_order) = legacy_order {
+                self.migrate_legacy_order(legacy_order);
+                migrated += 1;
+            }
+        }
+
+        let remaining: u64 = legacy.orders_umap.len() + legacy.copies_left;
+
+        if legacy.customer_index < self.customers_umap.len() || !legacy.orders_umap.is_empty() {
+            self.legacy_orders = Some(legacy);
+        }
+
+        log!("migrated {} orders successfully, {} left", migrated, remaining);
+
+        if self.legacy_orders.is_some() { remaining } else { 0 }
+    }
+
+    /**
+     * Takes one step through the legacy order copies of the given customer.
+     * @return the legacy order of the next copy, or None if the step deleted a copy or moved on to the next customer.
+    */
+    fn next_legacy_order(legacy: &mut LegacyOrders, customer_id: &AccountId) -> Option<LegacyOrder> {
+        let mut copies: Vector<LegacyOrder> = match legacy.customer_orders_lookup.get(customer_id) {
+            Some(copies) => copies,
+            None => {
+                legacy.customer_index += 1;
+                legacy.order_index = 0;
+
+                return None;
+            },
+        };
+
+        if legacy.order_index < copies.len() {
+            let order_id: String = copies.get(legacy.order_index).unwrap().id;
+
+            legacy.order_index += 1;
+
+            return legacy.orders_umap.remove(&order_id);
+        }
+
+        copies.pop();
+        legacy.copies_left = legacy.copies_left.saturating_sub(1);
+
+        if copies.is_empty() {
+            legacy.customer_orders_lookup.remove(customer_id);
+        } else {
+            legacy.customer_orders_lookup.insert(customer_id, &copies);
+        }
+
+        None
+    }
+
+    /**
+     * Saves the given legacy order in the current layout and adds it to the order list of its customer.
+    */
+    fn migrate_legacy_order(&mut self, legacy_order: LegacyOrder) {
+        let customer_id: AccountId = legacy_order.customer_id.clone();
+
+        let mut customer_orders: Vector<String> = self.create_customer_orders_list(customer_id.clone());
+        customer_orders.push(&legacy_order.id);
+        self.customer_orders_lookup.insert(&customer_id, &customer_orders);
+
+        let address: String = self.customers_umap.get(&customer_id).map(|customer| customer.full_address).unwrap_or_default();
+
+        self.save_order(&legacy_order.into_order(address, env::block_timestamp()));
+    }
+}
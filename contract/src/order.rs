@@ -2,6 +2,13 @@ use crate::Contract;
 use crate::ContractExt;
 
 use crate::ONE_YOCTO_NEAR;
+use crate::ORDER_ID_PREFIX;
+use crate::{ScheduleStatus, TimeWindow};
+use crate::OrderEvidence;
+use crate::AcceptedToken;
+use crate::ReviewReply;
+use crate::{OrderRating, MAX_FEEDBACK_EDITS, assert_valid_feedback_comment};
+use crate::Page;
 
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::collections::Vector;
@@ -15,10 +22,62 @@ use near_sdk::json_types::U128;
 #[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
 #[serde(crate = "near_sdk::serde")]
  pub enum OrderStatus {
-  Confirmed = 1,
-  InProgress,
+  PickupScheduled = 1,
+  PickedUp,
+  Washing,
+  ReadyForDelivery,
+  OutForDelivery,
   Delivered,
   Cancelled,
+  Disputed,
+  Refunded,
+  AwaitingTopUp,
+}
+
+/**
+ * all allowed order status transitions as (from, to) pairs
+ **/
+pub const ORDER_STATUS_TRANSITIONS: [(OrderStatus, OrderStatus); 16] = [
+  (OrderStatus::PickupScheduled, OrderStatus::PickedUp),
+  (OrderStatus::PickupScheduled, OrderStatus::Cancelled),
+  (OrderStatus::PickedUp, OrderStatus::Washing),
+  (OrderStatus::PickedUp, OrderStatus::Cancelled),
+  (OrderStatus::PickedUp, OrderStatus::AwaitingTopUp),
+  (OrderStatus::AwaitingTopUp, OrderStatus::PickedUp),
+  (OrderStatus::AwaitingTopUp, OrderStatus::Cancelled),
+  (OrderStatus::Washing, OrderStatus::ReadyForDelivery),
+  (OrderStatus::Washing, OrderStatus::Cancelled),
+  (OrderStatus::ReadyForDelivery, OrderStatus::OutForDelivery),
+  (OrderStatus::ReadyForDelivery, OrderStatus::Cancelled),
+  (OrderStatus::OutForDelivery, OrderStatus::Delivered),
+  (OrderStatus::OutForDelivery, OrderStatus::ReadyForDelivery),
+  (OrderStatus::Delivered, OrderStatus::Disputed),
+  (OrderStatus::Disputed, OrderStatus::Delivered),
+  (OrderStatus::Disputed, OrderStatus::Refunded),
+];
+
+impl OrderStatus {
+  /**
+   * Looks up the transition table for the current status.
+   * @return list of statuses the order can move to next.
+   */
+  pub fn next_statuses(&self) -> Vec<OrderStatus> {
+    ORDER_STATUS_TRANSITIONS
+      .iter()
+      .filter(|(from, _to)| from == self)
+      .map(|(_from, to)| *to)
+      .collect()
+  }
+
+  /**
+   * Checks the transition table for the given status change.
+   * @return boolean for the given status change.
+   */
+  pub fn can_transition_to(&self, next_status: &OrderStatus) -> bool {
+    ORDER_STATUS_TRANSITIONS
+      .iter()
+      .any(|(from, to)| from == self && to == next_status)
+  }
 }
 
 /**
@@ -28,6 +87,74 @@ use near_sdk::json_types::U128;
 #[serde(crate = "near_sdk::serde")]
 pub enum PaymentType {
   Prepaid = 1,
+  FungibleToken,
+  PayAfterWeighing,
+  CashOnDelivery,
+}
+
+/**
+ * all available garment types
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum GarmentType {
+  Shirt = 1,
+  TShirt,
+  Pants,
+  Jeans,
+  Pajamas,
+  Dress,
+  Suit,
+  Bedding,
+  Other,
+}
+
+pub const MAX_ORDER_ITEMS: usize = 50;
+pub const MAX_SPECIAL_INSTRUCTIONS_LENGTH: usize = 250;
+
+/**
+ * a line item requested by the customer
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NewOrderItem {
+  pub service_id: String,
+  pub garment_type: GarmentType,
+  pub quantity: u32,
+  pub weight_in_grams: u32,
+  pub special_instructions: String,
+}
+
+/**
+ * a priced line item of an order
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderItem {
+  pub service_id: String,
+  pub garment_type: GarmentType,
+  pub quantity: u32,
+  pub weight_in_grams: u32,
+  pub special_instructions: String,
+  pub price_in_yocto_near: U128,
+}
+
+impl OrderItem {
+  /**
+   * Sums the price of the given line items.
+   * @return total price in yoctoNEAR.
+   */
+  pub fn total_price(items: &[OrderItem]) -> u128 {
+    items.iter().fold(0, |total: u128, item: &OrderItem| u128::checked_add(total, item.price_in_yocto_near.0).unwrap())
+  }
+
+  /**
+   * Sums the weight of the given line items.
+   * @return total weight in grams.
+   */
+  pub fn total_weight(items: &[OrderItem]) -> u32 {
+    items.iter().fold(0, |total: u32, item: &OrderItem| u32::checked_add(total, item.weight_in_grams).unwrap())
+  }
 }
 
 /**
@@ -52,14 +179,31 @@ pub struct Order {
   pub id: String,
   pub customer_id: AccountId,
   pub description: String,
+  pub address: String,
+  pub items: Vec<OrderItem>,
   pub weight_in_grams: u32,
   pub price_in_yocto_near: U128,
+  pub refunded_in_yocto_near: U128,
+  pub amount_due_in_yocto_near: U128,
+  pub cash_paid_in_yocto_near: U128,
+  pub is_settled: bool,
   pub payment_type: PaymentType,
+  pub payment_token: Option<AcceptedToken>,
   pub status: OrderStatus,
   pub customer_feedback: CustomerFeedback,
   pub customer_feedback_comment: String,
+  pub rating: Option<OrderRating>,
+  pub feedback_edit_count: u8,
+  pub is_feedback_comment_hidden: bool,
+  pub feedback_date_time: Timestamp,
+  pub feedback_reply: Option<ReviewReply>,
+  pub pickup_window: TimeWindow,
+  pub delivery_window: TimeWindow,
+  pub schedule_status: ScheduleStatus,
   pub pickup_date_time: Timestamp,
   pub delivery_date_time: Timestamp,
+  pub delivered_by: Option<AccountId>,
+  pub evidence: Vec<OrderEvidence>,
 }
 
 #[near_bindgen]
@@ -72,25 +216,57 @@ impl Order {
       id: &String,
       customer_id: &AccountId,
       description: &String,
-      weight_in_grams: &u32,
-      price_in_yocto_near: &U128
+      address: &String,
+      items: &[OrderItem],
+      pickup_window: &TimeWindow,
+      delivery_window: &TimeWindow
     ) -> Order {
-      let order = Order {
+      Order {
           id: id.to_owned(),
           customer_id: customer_id.to_owned(),
           description: description.to_owned(),
-          weight_in_grams: weight_in_grams.to_owned(),
-          price_in_yocto_near: price_in_yocto_near.to_owned(),
+          address: address.to_owned(),
+          items: items.to_vec(),
+          weight_in_grams: OrderItem::total_weight(items),
+          price_in_yocto_near: U128(OrderItem::total_price(items)),
+          refunded_in_yocto_near: U128(0),
+          amount_due_in_yocto_near: U128(0),
+          cash_paid_in_yocto_near: U128(0),
+          is_settled: false,
           payment_type: PaymentType::Prepaid,
-          status: OrderStatus::Confirmed,
+          payment_token: None,
+          status: OrderStatus::PickupScheduled,
           customer_feedback: CustomerFeedback::None,
           customer_feedback_comment: "".to_string(),
-          pickup_date_time: env::block_timestamp(),
-          delivery_date_time: env::block_timestamp(),
-      };
-
-      order
+          rating: None,
+          feedback_edit_count: 0,
+          is_feedback_comment_hidden: false,
+          feedback_date_time: 0,
+          feedback_reply: None,
+          pickup_window: pickup_window.to_owned(),
+          delivery_window: delivery_window.to_owned(),
+          schedule_status: ScheduleStatus::Requested,
+          pickup_date_time: 0,
+          delivery_date_time: 0,
+          delivered_by: None,
+          evidence: Vec::new(),
+      }
     }
+  /**
+   * Calculates how much of the order price the customer has paid, into escrow or in cash.
+   * @return credited amount in yoctoNEAR.
+   */
+  pub(crate) fn credited_in_yocto_near(&self) -> u128 {
+    u128::checked_sub(self.price_in_yocto_near.0, self.amount_due_in_yocto_near.0).unwrap()
+  }
+
+  /**
+   * Calculates how much of the order price the customer has paid into escrow.
+   * @return paid amount in yoctoNEAR.
+   */
+  pub(crate) fn paid_in_yocto_near(&self) -> u128 {
+    u128::checked_sub(self.credited_in_yocto_near(), self.cash_paid_in_yocto_near.0).unwrap()
+  }
 }
 
 // Implement the contract structure
@@ -98,13 +274,13 @@ impl Order {
 impl Contract {
 
     /**
-   * Creates a order list for the given account ID.
-   * @return newly created Order list for the given account ID.
+   * Creates an order ID list for the given account ID.
+   * @return newly created order ID list for the given account ID.
    */
-    fn create_customer_orders_list(&mut self, account_id: AccountId) -> Vector<Order> {
+    pub(crate) fn create_customer_orders_list(&self, account_id: AccountId) -> Vector<String> {
       let customer_orders = self.customer_orders_lookup.get(&account_id).unwrap_or_else(|| {
         let prefix: Vec<u8> = [
-                b"s".as_slice(),
+                b"c".as_slice(),
                 &near_sdk::env::sha256_array(account_id.as_bytes()),
             ]
             .concat();
@@ -115,6 +291,24 @@ impl Contract {
       customer_orders
     }
 
+    /**
+   * Allocates the next order ID from the contract counter.
+   * Skips IDs already taken by client-supplied IDs of older orders, including legacy orders not migrated yet.
+   * @return newly allocated order ID.
+   */
+    fn generate_order_id(&mut self) -> String {
+      loop {
+        self.order_counter += 1;
+
+        let id: String = format!("{}-{}", ORDER_ID_PREFIX, self.order_counter);
+        let is_legacy_id: bool = self.legacy_orders.as_ref().is_some_and(|legacy| legacy.orders_umap.get(&id).is_some());
+
+        if !self.check_order_exists(&id) && !is_legacy_id {
+          return id;
+        }
+      }
+    }
+
     /**
    * Check if an order exists for the given order ID.
    * @return boolean for the given order ID.
@@ -126,131 +320,326 @@ impl Contract {
 
     /**
    * Creates an order for the given account ID.
+   * Prepaid orders are paid in full; pay-after-weighing and cash-on-delivery orders are booked with the booking deposit
+   * and the rest is due after weighing.
    * @return newly created Order object for the given account ID.
    */
     #[payable]
     pub fn create_order(
       &mut self,
-      id: String,
       customer_id: AccountId,
       description: String,
-      weight_in_grams: u32,
-      price_in_yocto_near: U128
+      items: Vec<NewOrderItem>,
+      pickup_window: TimeWindow,
+      delivery_window: TimeWindow,
+      payment_type: Option<PaymentType>
     ) -> Order {
-      require!(&env::predecessor_account_id().to_string() == &customer_id.to_string(), "You can only create your own order.");
+      require!(env::predecessor_account_id().to_string() == customer_id.to_string(), "You can only create your own order.");
       require!(self.check_customer_exists(&customer_id), "Customer does not exists.");
-      require!(!self.check_order_exists(&id), "Order already exists.");
+
+      let payment_type: PaymentType = payment_type.unwrap_or(PaymentType::Prepaid);
+
+      require!(payment_type != PaymentType::FungibleToken, "Token payments are made with ft_transfer_call.");
+
+      TimeWindow::assert_valid_schedule(&pickup_window, &delivery_window);
+
+      let order_items: Vec<OrderItem> = self.price_order_items(&items);
+      let price_in_yocto_near: U128 = U128(OrderItem::total_price(&order_items));
+      log!("price_in_yocto_near: {} yN", price_in_yocto_near.0);
+
+      self.book_pickup_slot(&pickup_window, OrderItem::total_weight(&order_items));
+
+      let amount_charged: u128 = if payment_type.is_deferred() {
+        std::cmp::min(self.booking_deposit_in_yocto_near, price_in_yocto_near.0)
+      } else {
+        price_in_yocto_near.0
+      };
 
       let deposit_amount: u128 = env::attached_deposit();
       let storage_used_before: u64 = env::storage_usage();
 
-      require!(&deposit_amount >= &(&price_in_yocto_near.0 + &ONE_YOCTO_NEAR), "Deposited amount must be greater than order amount by al least 1 Near.");
+      require!(deposit_amount >= amount_charged + ONE_YOCTO_NEAR, "Deposited amount must be greater than order amount by al least 1 Near.");
+
+      let mut order: Order = self.place_order(&customer_id, &description, &order_items, &pickup_window, &delivery_window);
+
+      if payment_type.is_deferred() {
+        order.payment_type = payment_type;
+        order.amount_due_in_yocto_near = U128(price_in_yocto_near.0 - amount_charged);
+
+        self.save_order(&order);
+      }
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+
+      let final_cost = u128::checked_add(amount_charged, final_storage_cost).unwrap();
+      log!("final_cost: {} yN", final_cost);
+
+      let surplus = u128::checked_sub(deposit_amount, final_cost).unwrap();
+
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id().clone()).transfer(surplus);
+        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      }
+
+      order
+    }
+
+    /**
+   * Stores a new order for the given account ID; the caller prices the items and books the pickup slot.
+   * @return newly created Order object.
+   */
+    pub(crate) fn place_order(
+      &mut self,
+      customer_id: &AccountId,
+      description: &String,
+      order_items: &[OrderItem],
+      pickup_window: &TimeWindow,
+      delivery_window: &TimeWindow
+    ) -> Order {
+      let id: String = self.generate_order_id();
+
+      let address: String = self.customers_umap.get(customer_id).unwrap().full_address;
 
       let order = Order::new(
           &id,
-          &customer_id,
-          &description,
-          &weight_in_grams,
-          &price_in_yocto_near,
+          customer_id,
+          description,
+          &address,
+          order_items,
+          pickup_window,
+          delivery_window,
       );
 
-      let mut customer_orders: Vector<Order> = self.create_customer_orders_list(customer_id.clone());
+      let mut customer_orders: Vector<String> = self.create_customer_orders_list(customer_id.clone());
 
-      customer_orders.push(&order);
+      customer_orders.push(&id);
 
-      self.customer_orders_lookup.insert(&customer_id, &customer_orders);
+      self.customer_orders_lookup.insert(customer_id, &customer_orders);
 
-      self.orders_umap.insert(&id, &order);
+      self.save_order(&order);
+
+      self.record_order_status_change(&id, None, order.status, None);
 
       log!("created order with id: {} successfully", &id);
 
+      order
+    }
+
+    /**
+   * Customer replaces the contents, address or schedule of their order while it awaits pickup.
+   * A lower price is refunded and a higher price is taken from the attached deposit, together with any change in storage cost.
+   * Pay-after-weighing and cash-on-delivery orders keep their booking deposit, refunded down to the new price, and owe the rest.
+   * A changed schedule has to be confirmed by the admin again.
+   * @return modified Order object for the given order ID.
+   */
+    #[payable]
+    pub fn modify_order(
+      &mut self,
+      order_id: String,
+      description: String,
+      items: Vec<NewOrderItem>,
+      address: String,
+      pickup_window: TimeWindow,
+      delivery_window: TimeWindow
+    ) -> Order {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(env::predecessor_account_id() == order.customer_id, "You can only modify your own order.");
+      require!(order.status == OrderStatus::PickupScheduled, "Order must have PickupScheduled status.");
+
+      TimeWindow::assert_valid_schedule(&pickup_window, &delivery_window);
+
+      let order_items: Vec<OrderItem> = self.price_order_items(&items);
+      let price_in_yocto_near: U128 = U128(OrderItem::total_price(&order_items));
+      log!("price_in_yocto_near: {} yN", price_in_yocto_near.0);
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      let paid: u128 = order.paid_in_yocto_near();
+      let target_paid: u128 = if order.payment_type.is_deferred() { std::cmp::min(paid, price_in_yocto_near.0) } else { price_in_yocto_near.0 };
+
+      require!(order.payment_token.is_none() || price_in_yocto_near.0 <= paid, "Orders paid in tokens can not be modified to a higher price.");
+
+      self.release_pickup_slot(&order.pickup_window, order.weight_in_grams);
+      self.book_pickup_slot(&pickup_window, OrderItem::total_weight(&order_items));
+
+      if order.pickup_window != pickup_window || order.delivery_window != delivery_window {
+        order.schedule_status = ScheduleStatus::Requested;
+      }
+
+      order.description = description;
+      order.address = address;
+      order.weight_in_grams = OrderItem::total_weight(&order_items);
+      order.price_in_yocto_near = price_in_yocto_near;
+      order.amount_due_in_yocto_near = U128(price_in_yocto_near.0 - target_paid);
+      order.items = order_items;
+      order.pickup_window = pickup_window;
+      order.delivery_window = delivery_window;
+
+      self.save_order(&order);
+
+      log!("modified order: {} successfully", &order_id);
+
       let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+      let storage_refund = self.calculate_storage_refund(storage_used_before);
+
+      let price_refund: u128 = paid.saturating_sub(target_paid);
+      let near_price_refund: u128 = if order.payment_token.is_none() { price_refund } else { 0 };
 
-      let final_cost = u128::checked_add(price_in_yocto_near.0, final_storage_cost).unwrap();
+      let credit = u128::checked_add(deposit_amount, near_price_refund).unwrap() + storage_refund;
+      let final_cost = u128::checked_add(target_paid.saturating_sub(paid), final_storage_cost).unwrap();
       log!("final_cost: {} yN", final_cost);
 
-      let surplus = u128::checked_sub(deposit_amount, final_cost).unwrap();
+      require!(credit >= final_cost, "Deposited amount must cover the price increase and storage cost.");
+
+      let surplus = credit - final_cost;
 
-      if &surplus > &0 {
-        Promise::new(env::predecessor_account_id().clone()).transfer(surplus.clone());
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(surplus);
         log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
       }
 
+      if order.payment_token.is_some() && price_refund > 0 {
+        self.transfer_order_funds(&order, &order.customer_id, price_refund, "refund");
+      }
+
       order
     }
 
+    /**
+   * Writes the given order to the order map and updates the secondary order indexes.
+   */
+    pub(crate) fn save_order(&mut self, order: &Order) {
+      let previous: Option<Order> = self.orders_umap.insert(&order.id, order);
+
+      self.update_order_indexes(previous.as_ref(), order);
+    }
+
+    /**
+   * Moves the given order to a new status if the transition table allows it,
+   * records the change in the order history and saves the order.
+   */
+    pub(crate) fn set_order_status(&mut self, order: &mut Order, order_status: OrderStatus, reason: Option<String>) {
+      if !order.status.can_transition_to(&order_status) {
+        env::panic_str(&format!("Order can not move from {:?} to {:?}.", order.status, order_status))
+      }
+
+      let previous_status: OrderStatus = order.status;
+
+      order.status = order_status;
+
+      if previous_status == OrderStatus::PickupScheduled && order_status == OrderStatus::Cancelled {
+        self.release_pickup_slot(&order.pickup_window, order.weight_in_grams);
+      }
+
+      if previous_status == OrderStatus::PickupScheduled && order_status == OrderStatus::PickedUp {
+        order.pickup_date_time = env::block_timestamp();
+      }
+
+      if previous_status == OrderStatus::OutForDelivery && order_status == OrderStatus::Delivered {
+        order.delivery_date_time = env::block_timestamp();
+        order.delivered_by = Some(env::predecessor_account_id());
+      }
+
+      self.save_order(order);
+
+      self.record_order_status_change(&order.id, Some(previous_status), order_status, reason);
+    }
+
     /**
    * Updates an order for the given order ID.
+   * Cancelled orders are refunded; payment for delivered orders stays in escrow until settle_order.
    * @return an updated Order object for the given order ID.
    */
-    pub fn update_order_status(&mut self, order_id: String, order_status: OrderStatus) -> Order {
+    pub fn update_order_status(&mut self, order_id: String, order_status: OrderStatus, reason: Option<String>) -> Order {
       require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can update order status.");
-      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      match self.apply_order_status(&order_id, order_status, reason) {
+        Ok(order) => order,
+        Err(error) => env::panic_str(&error)
+      }
+     }
+
+    /**
+   * Moves the given order to a new status on behalf of the admin and refunds it when cancelled.
+   * Every check runs before anything is written, so a failed update leaves the order untouched.
+   * @return an updated Order object, or the reason the update was rejected.
+   */
+    pub(crate) fn apply_order_status(&mut self, order_id: &String, order_status: OrderStatus, reason: Option<String>) -> Result<Order, String> {
+      if !self.check_order_exists(order_id) {
+        return Err("Order does not exists.".to_string());
+      }
 
       let storage_used_before: u64 = env::storage_usage();
 
-      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+      let mut order: Order = self.orders_umap.get(order_id).unwrap();
 
-      if &order_status == &OrderStatus::InProgress {
-        require!(&order.status == &OrderStatus::Confirmed, "Order must have Confirmed status.");
-      } else if &order_status == &OrderStatus::Delivered {
-        require!(&order.status == &OrderStatus::InProgress, "Order must have InProgress status.");
-      } else if &order_status == &OrderStatus::Cancelled {
-        require!(&order.status != &OrderStatus::Delivered, "Order has Delivered status.");
-      } else {
-        env::panic_str("Invalid operation")
+      Self::check_admin_order_status(&order, order_status)?;
+
+      if order_status == OrderStatus::Cancelled {
+        order.refunded_in_yocto_near = U128(order.paid_in_yocto_near());
+        order.is_settled = true;
       }
 
-      let mut customer_orders: Vector<Order> = self.customer_orders_lookup.get(&order.customer_id).unwrap();
+      self.set_order_status(&mut order, order_status, reason);
 
-      // let (index, found): (u64, Order) = customer_orders.iter().enumerate().find(|(index, mut found): (usize, Order)| -> Option<(u64, Order)> {
-      //   if &found.id == &order_id {
-      //     found.status = match order_status.clone() {
-      //       OrderStatus::Confirmed => env::panic_str("Can not set order status to Confirmed"),
-      //       OrderStatus::InProgress => OrderStatus::InProgress,
-      //       OrderStatus::Delivered => OrderStatus::Delivered,
-      //       OrderStatus::Cancelled => OrderStatus::Cancelled,
-      //     };
-      //   }
-      //   Some((index as u64, found))
-      // }).unwrap();
+      log!("updated order status successfully");
 
-      let mut iter = customer_orders.iter().enumerate().filter(|(_index, found)| found.id == order_id.clone());
+      if order.status == OrderStatus::Cancelled {
+        let final_storage_cost = self.charge_order_storage(&order, storage_used_before);
+        let surplus = order.paid_in_yocto_near().saturating_sub(final_storage_cost);
 
-      let (index, mut found) = iter.next().unwrap();
+        self.transfer_order_funds(&order, &order.customer_id, surplus, "surplus");
+      }
 
-      found.status = order_status.clone();
+      Ok(order)
+    }
 
-      customer_orders.replace(index as u64, &found);
+    /**
+   * Checks whether the admin can move the given order to the given status.
+   * @return nothing, or the reason the update would be rejected.
+   */
+    fn check_admin_order_status(order: &Order, order_status: OrderStatus) -> Result<(), String> {
+      if order_status == OrderStatus::Disputed || order_status == OrderStatus::Refunded {
+        return Err("Disputes are opened and resolved through the dispute methods.".to_string());
+      }
 
-      self.customer_orders_lookup.insert(&order.customer_id, &customer_orders);
+      if order_status == OrderStatus::AwaitingTopUp {
+        return Err("Top-ups are requested by recording the measured weight.".to_string());
+      }
 
-      order.status = order_status;
+      if order.status == OrderStatus::Disputed {
+        return Err("Disputed orders can only be moved by resolving the dispute.".to_string());
+      }
 
-      self.orders_umap.insert(&order_id, &order);
+      if order.status == OrderStatus::AwaitingTopUp && order_status != OrderStatus::Cancelled {
+        return Err("Order is awaiting a top-up from the customer.".to_string());
+      }
 
-      log!("updated order status successfully");
-      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
-      let surplus = u128::checked_sub(found.price_in_yocto_near.0.clone(), final_storage_cost).unwrap();
+      if !order.status.can_transition_to(&order_status) {
+        return Err(format!("Order can not move from {:?} to {:?}.", order.status, order_status));
+      }
 
-      if &order.status == &OrderStatus::Cancelled {
-        Promise::new(order.customer_id.clone()).transfer(surplus.clone());
-        log!("Transferred surplus: {} yN to account_id: {}", surplus, order.customer_id.clone());
+      if order.status == OrderStatus::PickedUp && order_status != OrderStatus::Cancelled && order.amount_due_in_yocto_near.0 > 0 && order.payment_type != PaymentType::CashOnDelivery {
+        return Err("Order must be weighed and paid before processing.".to_string());
+      }
 
-      } else if &order.status == &OrderStatus::Delivered {
-        Promise::new(env::predecessor_account_id().clone()).transfer(surplus.clone());
-        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      if order_status == OrderStatus::Delivered && order.amount_due_in_yocto_near.0 > 0 {
+        return Err("Order must be fully paid before delivery.".to_string());
       }
 
-      order
-     }
+      Ok(())
+    }
 
      /**
    * Customer can only fetch their own order details for given order ID .
    * @return an Order object for the given order ID.
    */
     pub fn get_order_by_id(&self, order_id: String) -> Order  {
-      if self.check_order_exists(&order_id) == false {
+      if !self.check_order_exists(&order_id) {
         env::panic_str("Order does not exist.")
       }
 
@@ -261,69 +650,99 @@ impl Contract {
       order
     }
 
+    /**
+   * Gets the statuses the given order can move to next.
+   * With admin_only set, keeps only those the admin can move it to with update_order_status.
+   * @return list of allowed next statuses for the given order ID.
+   */
+    pub fn get_next_order_statuses(&self, order_id: String, admin_only: Option<bool>) -> Vec<OrderStatus>  {
+      if !self.check_order_exists(&order_id) {
+        env::panic_str("Order does not exist.")
+      }
+
+      let order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      order.status
+        .next_statuses()
+        .into_iter()
+        .filter(|order_status| !admin_only.unwrap_or(false) || Self::check_admin_order_status(&order, *order_status).is_ok())
+        .collect()
+    }
+
     /**
    * Only the admin can call this function.
-   * @return Order list.
+   * @return a page of the Order list.
    */
-    pub fn get_order_list(&self) -> Vec<Order>  {
+    pub fn get_order_list(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Order>  {
        require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can call this function.");
 
-      let orders = self.orders_umap.values_as_vector().to_vec();
-      orders
+      Page::from_vector(self.orders_umap.values_as_vector(), from_index, limit)
     }
 
-    pub fn get_orders_by_customer_id(&mut self, customer_id: AccountId) -> Vec<Order> {
-      require!(&env::predecessor_account_id().to_string() == &customer_id.to_string(), "You can only fetch your own orders.");
+    /**
+   * Customer can only fetch their own orders, while the admin can fetch the orders of any customer.
+   * @return a page of the Order list for the given account ID.
+   */
+    pub fn get_orders_by_customer_id(&self, customer_id: AccountId, from_index: Option<u64>, limit: Option<u64>) -> Page<Order> {
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == customer_id || self.check_is_admin(&caller), "You can only fetch your own orders.");
       require!(self.check_customer_exists(&customer_id), "Customer does not exists.");
 
-      let customer_orders: Vector<Order> = self.create_customer_orders_list(customer_id.clone());
+      let customer_orders: Vector<String> = self.create_customer_orders_list(customer_id.clone());
 
-      customer_orders.to_vec()
+      Page::from_vector(&customer_orders, from_index, limit).map(|order_id| self.orders_umap.get(&order_id).unwrap())
     }
 
     /**
-   * Submits feedback for the given order ID.
+   * Submits feedback for the given order ID within the feedback window after delivery.
+   * Feedback can be edited once, and the edited rating replaces the earlier one in the aggregates.
    * @return updated Order object for the given order ID.
    */
     #[payable]
-    pub fn submit_feedback(&mut self, order_id: String, customer_feedback: CustomerFeedback, customer_feedback_comment: String) -> Order {
+    pub fn submit_feedback(&mut self, order_id: String, rating: OrderRating, customer_feedback_comment: String) -> Order {
       require!(self.check_order_exists(&order_id), "Order does not exists.");
 
       let storage_used_before: u64 = env::storage_usage();
 
       let mut order: Order = self.orders_umap.get(&order_id).unwrap();
 
-      require!(&env::predecessor_account_id().to_string() == &order.customer_id.to_string(), "You can only submit feedback for your own orders.");
-      require!(&env::attached_deposit().clone() >= &ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(env::predecessor_account_id().to_string() == order.customer_id.to_string(), "You can only submit feedback for your own orders.");
+      require!(env::attached_deposit() >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
 
 
-      if &order.status != &OrderStatus::Delivered {
+      if order.status != OrderStatus::Delivered {
         env::panic_str("Order must have Delivered status.");
       }
 
-      let mut customer_orders: Vector<Order> = self.customer_orders_lookup.get(&order.customer_id).unwrap();
+      require!(env::block_timestamp() <= order.delivery_date_time.saturating_add(self.feedback_window), "Feedback window has closed.");
 
-      let (index, found): (u64, Order) = customer_orders.iter().enumerate().find_map(|(index, mut found): (usize, Order)| -> Option<(u64, Order)> {
-        if &found.id == &order_id {
-          found.customer_feedback = customer_feedback;
-          found.customer_feedback_comment = customer_feedback_comment.clone();
-        }
-        Some((index as u64, found))
-      }).unwrap();
+      rating.assert_valid();
+      assert_valid_feedback_comment(&customer_feedback_comment);
+
+      if let Some(previous_rating) = order.rating {
+        require!(order.feedback_edit_count < MAX_FEEDBACK_EDITS, "Feedback can only be edited once.");
+
+        order.feedback_edit_count += 1;
+        self.apply_order_rating(&order, &previous_rating, false);
+      } else {
+        self.reviewed_orders_vec.push(&order_id);
+      }
 
-      customer_orders.replace(index, &found);
-      self.customer_orders_lookup.insert(&order.customer_id, &customer_orders);
+      self.apply_order_rating(&order, &rating, true);
 
-      order.customer_feedback = customer_feedback;
+      order.rating = Some(rating);
+      order.customer_feedback = rating.to_customer_feedback();
       order.customer_feedback_comment = customer_feedback_comment;
-      self.orders_umap.insert(&order_id, &order);
+      order.feedback_date_time = env::block_timestamp();
+      self.save_order(&order);
 
       log!("submitted order feedback successfully");
       let final_storage_cost = self.calculate_storage_cost(storage_used_before);
-      let surplus = u128::checked_sub(env::attached_deposit().clone(), final_storage_cost).unwrap();
+      let surplus = u128::checked_sub(env::attached_deposit(), final_storage_cost).unwrap();
 
-      Promise::new(found.customer_id.clone()).transfer(surplus.clone());
-      log!("Transferred surplus: {} yN to account_id: {}", &surplus, &found.customer_id);
+      Promise::new(order.customer_id.clone()).transfer(surplus);
+      log!("Transferred surplus: {} yN to account_id: {}", &surplus, &order.customer_id);
 
       order
     }
@@ -0,0 +1,117 @@
+use near_sdk::borsh::BorshDeserialize;
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::collections::Vector;
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::require;
+
+pub const DEFAULT_PAGE_LIMIT: u64 = 50;
+pub const MAX_PAGE_LIMIT: u64 = 100;
+
+/**
+ * a page of a list view along with the total number of entries in the list
+ **/
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Page<T> {
+  pub items: Vec<T>,
+  pub from_index: u64,
+  pub total: u64,
+}
+
+impl<T> Page<T> {
+  /**
+   * Clamps the requested page size to the allowed range.
+   * @return number of entries to return.
+   */
+  pub fn limit(limit: Option<u64>) -> u64 {
+    std::cmp::min(limit.unwrap_or(DEFAULT_PAGE_LIMIT), MAX_PAGE_LIMIT)
+  }
+
+  /**
+   * Reads a page of entries from the given persistent vector.
+   * @return Page of at most limit entries starting at from_index.
+   */
+  pub fn from_vector(vector: &Vector<T>, from_index: Option<u64>, limit: Option<u64>) -> Page<T>
+  where
+    T: BorshSerialize + BorshDeserialize,
+  {
+    let from: u64 = from_index.unwrap_or(0);
+    let to: u64 = std::cmp::min(vector.len(), from.saturating_add(Page::<T>::limit(limit)));
+
+    Page {
+      items: (from..to).filter_map(|index| vector.get(index)).collect(),
+      from_index: from,
+      total: vector.len(),
+    }
+  }
+
+  /**
+   * Converts the entries of the page, keeping its position and total.
+   * @return Page of converted entries.
+   */
+  pub fn map<U, F>(self, f: F) -> Page<U>
+  where
+    F: FnMut(T) -> U,
+  {
+    Page {
+      items: self.items.into_iter().map(f).collect(),
+      from_index: self.from_index,
+      total: self.total,
+    }
+  }
+}
+
+/**
+ * a page of a list view read from a sorted index along with the number of entries in that index and the key to read the next page after
+ **/
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CursorPage<T, K> {
+  pub items: Vec<T>,
+  pub next_cursor: Option<K>,
+  pub total: u64,
+}
+
+impl<T, K> CursorPage<T, K> {
+  /**
+   * Takes a page of keyed entries from an iterator that already starts after the requested cursor.
+   * A zero limit is rejected, since an empty page could not carry the cursor forward.
+   * @return CursorPage of at most limit entries, with the key of the last one when more entries follow.
+   */
+  pub fn from_iter<I>(iter: I, total: u64, limit: Option<u64>) -> CursorPage<T, K>
+  where
+    I: Iterator<Item = (K, T)>,
+  {
+    let limit: usize = Page::<T>::limit(limit) as usize;
+
+    require!(limit > 0, "Page limit must be greater than zero.");
+
+    let mut entries: Vec<(K, T)> = iter.take(limit + 1).collect();
+    let has_more: bool = entries.len() > limit;
+
+    entries.truncate(limit);
+
+    let (keys, items): (Vec<K>, Vec<T>) = entries.into_iter().unzip();
+
+    CursorPage {
+      items,
+      next_cursor: if has_more { keys.into_iter().last() } else { None },
+      total,
+    }
+  }
+
+  /**
+   * Converts the entries of the page, keeping its cursor and total.
+   * @return CursorPage of converted entries.
+   */
+  pub fn map<U, F>(self, f: F) -> CursorPage<U, K>
+  where
+    F: FnMut(T) -> U,
+  {
+    CursorPage {
+      items: self.items.into_iter().map(f).collect(),
+      next_cursor: self.next_cursor,
+      total: self.total,
+    }
+  }
+}
@@ -0,0 +1,81 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::{Order, OrderStatus, PaymentType};
+
+use near_sdk::{near_bindgen, env, log, require};
+use near_sdk::json_types::U128;
+
+pub const DEFAULT_BOOKING_DEPOSIT: u128 = ONE_YOCTO_NEAR / 2;
+
+impl PaymentType {
+  /**
+   * Checks whether orders of this payment type are booked with a deposit and paid in full later.
+   * @return true for pay-after-weighing and cash-on-delivery orders.
+   */
+  pub fn is_deferred(&self) -> bool {
+    *self == PaymentType::PayAfterWeighing || *self == PaymentType::CashOnDelivery
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Sets the deposit that books a pay-after-weighing or cash-on-delivery order, in yoctoNEAR.
+   * @return the new booking deposit.
+   */
+    pub fn set_booking_deposit(&mut self, booking_deposit_in_yocto_near: U128) -> U128 {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can set booking deposit.");
+
+      self.booking_deposit_in_yocto_near = booking_deposit_in_yocto_near.0;
+
+      log!("updated booking deposit to: {} yN successfully", booking_deposit_in_yocto_near.0);
+
+      U128(self.booking_deposit_in_yocto_near)
+    }
+
+    /**
+   * Gets the deposit that books a pay-after-weighing or cash-on-delivery order, in yoctoNEAR.
+   * @return the booking deposit.
+   */
+    pub fn get_booking_deposit(&self) -> U128 {
+      U128(self.booking_deposit_in_yocto_near)
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Records a cash payment the shop received off-chain towards the amount due of a picked up order.
+   * An order awaiting a top-up returns to PickedUp status once nothing is due.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn confirm_cash_payment(&mut self, order_id: String, amount_in_yocto_near: U128) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can confirm cash payments.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(order.payment_type.is_deferred(), "Order does not accept cash payments.");
+      require!(
+        matches!(order.status, OrderStatus::PickedUp | OrderStatus::AwaitingTopUp | OrderStatus::Washing | OrderStatus::ReadyForDelivery | OrderStatus::OutForDelivery),
+        "Cash can only be received between pickup and delivery."
+      );
+      require!(amount_in_yocto_near.0 > 0 && amount_in_yocto_near.0 <= order.amount_due_in_yocto_near.0, "Amount must be between 1 yN and the amount due.");
+
+      order.amount_due_in_yocto_near = U128(order.amount_due_in_yocto_near.0 - amount_in_yocto_near.0);
+      order.cash_paid_in_yocto_near = U128(order.cash_paid_in_yocto_near.0 + amount_in_yocto_near.0);
+
+      if order.amount_due_in_yocto_near.0 == 0 && order.status == OrderStatus::AwaitingTopUp {
+        self.set_order_status(&mut order, OrderStatus::PickedUp, Some("Paid in cash.".to_string()));
+      } else {
+        self.save_order(&order);
+      }
+
+      log!("confirmed cash payment: {} yN for order: {} successfully", amount_in_yocto_near.0, &order_id);
+
+      order
+    }
+}
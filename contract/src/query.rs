@@ -0,0 +1,131 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderStatus};
+use crate::{CursorPage, Page};
+
+use near_sdk::collections::{UnorderedSet, TreeMap};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, Timestamp, env, require};
+use std::ops::Bound;
+
+/**
+ * order dates that can be queried by range
+ **/
+#[derive(Deserialize, Serialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum OrderDateField {
+  Pickup = 1,
+  Delivery,
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Creates an order ID set for the given order status.
+   * @return newly created order ID set for the given order status.
+   */
+    fn create_status_orders_set(&self, order_status: OrderStatus) -> UnorderedSet<String> {
+      self.status_orders_lookup.get(&order_status).unwrap_or_else(|| {
+        let prefix: Vec<u8> = [
+                b"t".as_slice(),
+                &[order_status as u8],
+            ]
+            .concat();
+
+          UnorderedSet::new(prefix)
+      })
+    }
+
+    /**
+   * Adds or removes the given order ID in the set of the given order status.
+   */
+    fn set_status_index(&mut self, order_status: OrderStatus, order_id: &String, is_member: bool) {
+      let mut status_orders: UnorderedSet<String> = self.create_status_orders_set(order_status);
+
+      if is_member {
+        status_orders.insert(order_id);
+      } else {
+        status_orders.remove(order_id);
+      }
+
+      self.status_orders_lookup.insert(&order_status, &status_orders);
+    }
+
+    /**
+   * Brings the status, pickup date and delivery date indexes in line with the given order.
+   * Only the entries that differ from the previously saved version of the order are rewritten.
+   */
+    pub(crate) fn update_order_indexes(&mut self, previous: Option<&Order>, order: &Order) {
+      let id: &String = &order.id;
+
+      if previous.map(|previous| previous.status) != Some(order.status) {
+        if let Some(previous) = previous {
+          self.set_status_index(previous.status, id, false);
+        }
+        self.set_status_index(order.status, id, true);
+      }
+
+      if previous.map(|previous| previous.pickup_window.start) != Some(order.pickup_window.start) {
+        if let Some(previous) = previous {
+          self.pickup_orders_tmap.remove(&(previous.pickup_window.start, id.clone()));
+        }
+        self.pickup_orders_tmap.insert(&(order.pickup_window.start, id.clone()), &());
+      }
+
+      if previous.map(|previous| previous.delivery_window.start) != Some(order.delivery_window.start) {
+        if let Some(previous) = previous {
+          self.delivery_orders_tmap.remove(&(previous.delivery_window.start, id.clone()));
+        }
+        self.delivery_orders_tmap.insert(&(order.delivery_window.start, id.clone()), &());
+      }
+    }
+
+    /**
+   * Only the admin can call this function.
+   * @return a page of orders with the given status.
+   */
+    pub fn get_orders_by_status(&self, order_status: OrderStatus, from_index: Option<u64>, limit: Option<u64>) -> Page<Order>  {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can call this function.");
+
+      let status_orders: UnorderedSet<String> = self.create_status_orders_set(order_status);
+
+      Page::from_vector(status_orders.as_vector(), from_index, limit).map(|order_id| self.orders_umap.get(&order_id).unwrap())
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Pages continue after the (start, order ID) cursor returned with the previous page.
+   * The page total counts every order in the pickup or delivery index, not only those within the range.
+   * @return a page of orders whose pickup or delivery window starts within [from, to), ordered by that start.
+   */
+    pub fn get_orders_by_date_range(
+      &self,
+      date_field: OrderDateField,
+      from: Timestamp,
+      to: Timestamp,
+      after: Option<(Timestamp, String)>,
+      limit: Option<u64>
+    ) -> CursorPage<Order, (Timestamp, String)>  {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can call this function.");
+      require!(from < to, "Date range must end after it starts.");
+
+      let date_orders: &TreeMap<(Timestamp, String), ()> = match date_field {
+        OrderDateField::Pickup => &self.pickup_orders_tmap,
+        OrderDateField::Delivery => &self.delivery_orders_tmap,
+      };
+
+      let lower_bound: Bound<(Timestamp, String)> = match after {
+        Some(cursor) if cursor.0 >= to => return CursorPage { items: Vec::new(), next_cursor: None, total: date_orders.len() },
+        Some(cursor) if cursor.0 >= from => Bound::Excluded(cursor),
+        _ => Bound::Included((from, String::new())),
+      };
+
+      let range = date_orders.range((lower_bound, Bound::Excluded((to, String::new()))));
+
+      CursorPage::from_iter(range.map(|(key, _)| (key.clone(), key.1)), date_orders.len(), limit)
+        .map(|order_id| self.orders_umap.get(&order_id).unwrap())
+    }
+}
@@ -0,0 +1,200 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{CustomerFeedback, Order};
+use crate::char_count;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, env, log, require};
+
+pub const MIN_RATING: u8 = 1;
+pub const MAX_RATING: u8 = 5;
+pub const DEFAULT_FEEDBACK_WINDOW: u64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_FEEDBACK_EDITS: u8 = 1;
+pub const MAX_FEEDBACK_COMMENT_LENGTH: usize = 500;
+
+/**
+ * Checks that a feedback comment fits the length limit and holds no control characters other than line breaks.
+ */
+pub fn assert_valid_feedback_comment(comment: &str) {
+  require!(char_count(comment) <= MAX_FEEDBACK_COMMENT_LENGTH, "Feedback comment must be at most 500 characters.");
+  require!(comment.chars().all(|c| !c.is_control() || c == '\n'), "Feedback comment contains invalid characters.");
+}
+
+/**
+ * a customer rating of a delivered order, from 1 to 5 stars on each dimension
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OrderRating {
+  pub wash_quality: u8,
+  pub punctuality: u8,
+  pub staff_behaviour: u8,
+}
+
+impl OrderRating {
+  /**
+   * Checks that every dimension is rated from 1 to 5 stars.
+   */
+  pub fn assert_valid(&self) {
+    require!(
+      [self.wash_quality, self.punctuality, self.staff_behaviour].iter().all(|stars| (MIN_RATING..=MAX_RATING).contains(stars)),
+      "Ratings must be between 1 and 5 stars."
+    );
+  }
+
+  /**
+   * Maps the rounded average of all dimensions onto the customer feedback scale.
+   * @return CustomerFeedback for this rating.
+   */
+  pub fn to_customer_feedback(&self) -> CustomerFeedback {
+    let sum: u32 = self.wash_quality as u32 + self.punctuality as u32 + self.staff_behaviour as u32;
+
+    match (sum + 1) / 3 {
+      5 => CustomerFeedback::Excellent,
+      4 => CustomerFeedback::Good,
+      3 => CustomerFeedback::Average,
+      2 => CustomerFeedback::Bad,
+      _ => CustomerFeedback::Worst,
+    }
+  }
+}
+
+/**
+ * running aggregate of the ratings given on one dimension
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RatingStats {
+  pub count: u64,
+  pub sum: u64,
+  pub distribution: [u64; 5],
+}
+
+impl RatingStats {
+  /**
+   * Counts the given number of stars in or out of the aggregate.
+   */
+  fn apply(&mut self, stars: u8, is_added: bool) {
+    let bucket: usize = (stars - MIN_RATING) as usize;
+
+    if is_added {
+      self.count += 1;
+      self.sum += stars as u64;
+      self.distribution[bucket] += 1;
+    } else {
+      self.count -= 1;
+      self.sum -= stars as u64;
+      self.distribution[bucket] -= 1;
+    }
+  }
+}
+
+/**
+ * running aggregates of the ratings given on every dimension
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FeedbackStats {
+  pub wash_quality: RatingStats,
+  pub punctuality: RatingStats,
+  pub staff_behaviour: RatingStats,
+}
+
+impl FeedbackStats {
+  /**
+   * Counts the given rating in or out of every dimension.
+   */
+  pub fn apply(&mut self, rating: &OrderRating, is_added: bool) {
+    self.wash_quality.apply(rating.wash_quality, is_added);
+    self.punctuality.apply(rating.punctuality, is_added);
+    self.staff_behaviour.apply(rating.staff_behaviour, is_added);
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Sets how long after delivery a customer can submit or edit feedback, in nanoseconds.
+   * @return the new feedback window.
+   */
+    pub fn set_feedback_window(&mut self, feedback_window: u64) -> u64 {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can set feedback window.");
+
+      self.feedback_window = feedback_window;
+
+      log!("updated feedback window to: {} ns successfully", feedback_window);
+
+      self.feedback_window
+    }
+
+    /**
+   * Gets how long after delivery a customer can submit or edit feedback, in nanoseconds.
+   * @return the feedback window.
+   */
+    pub fn get_feedback_window(&self) -> u64 {
+      self.feedback_window
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Hides or shows the feedback comment of the given order ID. The rating still counts in the aggregates.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn set_feedback_comment_hidden(&mut self, order_id: String, is_hidden: bool) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can moderate feedback.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(order.rating.is_some(), "Order has no feedback.");
+
+      order.is_feedback_comment_hidden = is_hidden;
+      self.save_order(&order);
+
+      log!("set feedback comment hidden: {} for order: {} successfully", is_hidden, &order_id);
+
+      order
+    }
+
+    /**
+   * Counts the given rating in or out of the aggregates of every service in the order and of the admin who delivered it.
+   */
+    pub(crate) fn apply_order_rating(&mut self, order: &Order, rating: &OrderRating, is_added: bool) {
+      let mut service_ids: Vec<&String> = order.items.iter().map(|item| &item.service_id).collect();
+      service_ids.sort();
+      service_ids.dedup();
+
+      for service_id in service_ids {
+        let mut stats: FeedbackStats = self.service_feedback_stats_lookup.get(service_id).unwrap_or_default();
+        stats.apply(rating, is_added);
+        self.service_feedback_stats_lookup.insert(service_id, &stats);
+      }
+
+      if let Some(operator_id) = &order.delivered_by {
+        let mut stats: FeedbackStats = self.operator_feedback_stats_lookup.get(operator_id).unwrap_or_default();
+        stats.apply(rating, is_added);
+        self.operator_feedback_stats_lookup.insert(operator_id, &stats);
+      }
+    }
+
+    /**
+   * Gets the aggregated ratings of orders that included the given service.
+   * @return FeedbackStats object for the given service ID.
+   */
+    pub fn get_service_feedback_stats(&self, service_id: String) -> FeedbackStats {
+      self.service_feedback_stats_lookup.get(&service_id).unwrap_or_default()
+    }
+
+    /**
+   * Gets the aggregated ratings of orders delivered by the given admin.
+   * @return FeedbackStats object for the given account ID.
+   */
+    pub fn get_operator_feedback_stats(&self, account_id: AccountId) -> FeedbackStats {
+      self.operator_feedback_stats_lookup.get(&account_id).unwrap_or_default()
+    }
+}
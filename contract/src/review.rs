@@ -0,0 +1,97 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderRating};
+use crate::char_count;
+use crate::Page;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, Timestamp, env, log, require};
+
+pub const MAX_REVIEW_REPLY_LENGTH: usize = 500;
+
+/**
+ * an admin reply to the feedback of an order
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ReviewReply {
+  pub body: String,
+  pub timestamp: Timestamp,
+}
+
+/**
+ * an anonymized view of the feedback of an order for the public review feed
+ **/
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Review {
+  pub rating: OrderRating,
+  pub comment: Option<String>,
+  pub service_ids: Vec<String>,
+  pub reviewed: Timestamp,
+  pub reply: Option<ReviewReply>,
+}
+
+impl Review {
+  /**
+   * Builds the public view of the feedback of the given order, leaving out the customer and hidden comments.
+   * @return Review object for the given order.
+   */
+  fn from_order(order: Order) -> Review {
+    let mut service_ids: Vec<String> = order.items.into_iter().map(|item| item.service_id).collect();
+    service_ids.sort();
+    service_ids.dedup();
+
+    let is_comment_shown: bool = !order.is_feedback_comment_hidden && !order.customer_feedback_comment.is_empty();
+
+    Review {
+      rating: order.rating.unwrap(),
+      comment: if is_comment_shown { Some(order.customer_feedback_comment) } else { None },
+      service_ids,
+      reviewed: order.feedback_date_time,
+      reply: order.feedback_reply,
+    }
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Attaches a public reply to the feedback of the given order ID, replacing any earlier reply.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn reply_to_review(&mut self, order_id: String, body: String) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can reply to reviews.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+      require!(!body.is_empty() && char_count(&body) <= MAX_REVIEW_REPLY_LENGTH, "Reply must be between 1 and 500 characters.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(order.rating.is_some(), "Order has no feedback.");
+
+      order.feedback_reply = Some(ReviewReply {
+        body,
+        timestamp: env::block_timestamp(),
+      });
+
+      self.save_order(&order);
+
+      log!("replied to review of order: {} successfully", &order_id);
+
+      order
+    }
+
+    /**
+   * Gets the public review feed without any customer details.
+   * @return a page of reviews, oldest first.
+   */
+    pub fn get_reviews(&self, from_index: Option<u64>, limit: Option<u64>) -> Page<Review> {
+      Page::from_vector(&self.reviewed_orders_vec, from_index, limit)
+        .map(|order_id| Review::from_order(self.orders_umap.get(&order_id).unwrap()))
+    }
+}
@@ -0,0 +1,121 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderStatus};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, Timestamp, env, log, require};
+
+/**
+ * all available schedule statuses
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ScheduleStatus {
+  Requested = 1,
+  Proposed,
+  Confirmed,
+}
+
+/**
+ * a time window in nanoseconds since the unix epoch
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TimeWindow {
+  pub start: Timestamp,
+  pub end: Timestamp,
+}
+
+impl TimeWindow {
+  /**
+   * Checks that the pickup window lies in the future and ends before the delivery window starts.
+   */
+  pub fn assert_valid_schedule(pickup_window: &TimeWindow, delivery_window: &TimeWindow) {
+    require!(pickup_window.start > env::block_timestamp(), "Pickup window must be in the future.");
+    require!(pickup_window.start < pickup_window.end, "Pickup window must end after it starts.");
+    require!(delivery_window.start < delivery_window.end, "Delivery window must end after it starts.");
+    require!(pickup_window.end <= delivery_window.start, "Delivery window must start after the pickup window ends.");
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Confirms the requested pickup and delivery windows for the given order ID.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn confirm_order_schedule(&mut self, order_id: String) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can confirm order schedule.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(order.status == OrderStatus::PickupScheduled, "Order must have PickupScheduled status.");
+      require!(order.schedule_status == ScheduleStatus::Requested, "Order schedule must have Requested status.");
+
+      order.schedule_status = ScheduleStatus::Confirmed;
+
+      self.save_order(&order);
+
+      log!("confirmed schedule of order: {} successfully", &order_id);
+
+      order
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Proposes new pickup and delivery windows for the given order ID, to be accepted by the customer.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn propose_order_schedule(&mut self, order_id: String, pickup_window: TimeWindow, delivery_window: TimeWindow) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can propose order schedule.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      TimeWindow::assert_valid_schedule(&pickup_window, &delivery_window);
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(order.status == OrderStatus::PickupScheduled, "Order must have PickupScheduled status.");
+
+      self.release_pickup_slot(&order.pickup_window, order.weight_in_grams);
+      self.book_pickup_slot(&pickup_window, order.weight_in_grams);
+
+      order.pickup_window = pickup_window;
+      order.delivery_window = delivery_window;
+      order.schedule_status = ScheduleStatus::Proposed;
+
+      self.save_order(&order);
+
+      log!("proposed schedule of order: {} successfully", &order_id);
+
+      order
+    }
+
+    /**
+   * Customer accepts the pickup and delivery windows proposed by the admin for the given order ID.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn accept_order_schedule(&mut self, order_id: String) -> Order {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(env::predecessor_account_id() == order.customer_id, "You can only accept schedule of your own order.");
+      require!(order.status == OrderStatus::PickupScheduled, "Order must have PickupScheduled status.");
+      require!(order.schedule_status == ScheduleStatus::Proposed, "Order schedule must have Proposed status.");
+      require!(order.pickup_window.start > env::block_timestamp(), "Proposed pickup window has already started.");
+
+      order.schedule_status = ScheduleStatus::Confirmed;
+
+      self.save_order(&order);
+
+      log!("accepted schedule of order: {} successfully", &order_id);
+
+      order
+    }
+}
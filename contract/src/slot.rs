@@ -0,0 +1,210 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::TimeWindow;
+use crate::CursorPage;
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, Timestamp, env, log, require};
+
+/**
+ * an admin-configured pickup slot; a zero capacity means no limit on that dimension
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PickupSlot {
+  pub window: TimeWindow,
+  pub max_orders: u32,
+  pub max_weight_in_grams: u32,
+  pub booked_orders: u32,
+  pub booked_weight_in_grams: u32,
+  pub created: Timestamp,
+  pub updated: Timestamp,
+}
+
+/**
+ * remaining capacity of a pickup slot; None means no limit
+ **/
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PickupSlotAvailability {
+  pub window: TimeWindow,
+  pub remaining_orders: Option<u32>,
+  pub remaining_weight_in_grams: Option<u32>,
+}
+
+impl PickupSlot {
+  /**
+   * Checks if the slot can take another order of the given weight.
+   * @return boolean for the given weight.
+   */
+  pub fn can_book(&self, weight_in_grams: u32) -> bool {
+    let orders_fit: bool = self.max_orders == 0 || self.booked_orders < self.max_orders;
+    let weight_fits: bool = self.max_weight_in_grams == 0
+      || u32::checked_add(self.booked_weight_in_grams, weight_in_grams).unwrap() <= self.max_weight_in_grams;
+
+    orders_fit && weight_fits
+  }
+
+  /**
+   * Gets the remaining capacity of the slot.
+   * @return PickupSlotAvailability object for this slot.
+   */
+  pub fn availability(&self) -> PickupSlotAvailability {
+    PickupSlotAvailability {
+      window: self.window,
+      remaining_orders: if self.max_orders == 0 { None } else { Some(self.max_orders.saturating_sub(self.booked_orders)) },
+      remaining_weight_in_grams: if self.max_weight_in_grams == 0 { None } else { Some(self.max_weight_in_grams.saturating_sub(self.booked_weight_in_grams)) },
+    }
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * @return newly created PickupSlot object for the given window.
+   */
+    pub fn create_pickup_slot(&mut self, window: TimeWindow, max_orders: u32, max_weight_in_grams: u32) -> PickupSlot {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can create pickup slots.");
+      require!(window.start > env::block_timestamp(), "Pickup slot must be in the future.");
+      require!(window.start < window.end, "Pickup slot must end after it starts.");
+      require!(self.pickup_slots_tmap.get(&window.start).is_none(), "Pickup slot already exists.");
+
+      let slot = PickupSlot {
+        window,
+        max_orders,
+        max_weight_in_grams,
+        booked_orders: 0,
+        booked_weight_in_grams: 0,
+        created: env::block_timestamp(),
+        updated: env::block_timestamp(),
+      };
+
+      self.save_pickup_slot(&slot);
+
+      log!("created pickup slot starting at: {} successfully", window.start);
+
+      slot
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Capacities may be lowered below what is already booked; that only stops new bookings.
+   * @return updated PickupSlot object for the given slot start.
+   */
+    pub fn update_pickup_slot(&mut self, start: Timestamp, max_orders: u32, max_weight_in_grams: u32) -> PickupSlot {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can update pickup slots.");
+
+      let mut slot: PickupSlot = self.get_pickup_slot(start);
+
+      slot.max_orders = max_orders;
+      slot.max_weight_in_grams = max_weight_in_grams;
+      slot.updated = env::block_timestamp();
+
+      self.save_pickup_slot(&slot);
+
+      log!("updated pickup slot starting at: {} successfully", start);
+
+      slot
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Slots with booked orders can only be deleted once their window has passed.
+   * @return deleted PickupSlot object for the given slot start.
+   */
+    pub fn delete_pickup_slot(&mut self, start: Timestamp) -> PickupSlot {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can delete pickup slots.");
+
+      let slot: PickupSlot = self.get_pickup_slot(start);
+
+      require!(slot.booked_orders == 0 || slot.window.end <= env::block_timestamp(), "Pickup slot has booked orders.");
+
+      self.pickup_slots_tmap.remove(&start);
+
+      if slot.can_book(0) {
+        self.available_pickup_slots_count -= 1;
+      }
+
+      log!("deleted pickup slot starting at: {} successfully", start);
+
+      slot
+    }
+
+    /**
+   * Gets the pickup slot for the given slot start.
+   * @return PickupSlot object for the given slot start.
+   */
+    pub fn get_pickup_slot(&self, start: Timestamp) -> PickupSlot  {
+      match self.pickup_slots_tmap.get(&start) {
+        Some(slot) => slot,
+        None => env::panic_str("Pickup slot does not exist.")
+      }
+    }
+
+    /**
+   * Lists upcoming pickup slots that still have capacity, ordered by start time.
+   * Pages continue after the slot start returned with the previous page.
+   * The page total counts slots with free capacity until they are deleted, including ones whose window has passed.
+   * @return a page of slot availabilities.
+   */
+    pub fn get_available_pickup_slots(&self, after: Option<Timestamp>, limit: Option<u64>) -> CursorPage<PickupSlotAvailability, Timestamp>  {
+      let now: Timestamp = env::block_timestamp();
+
+      let available = self.pickup_slots_tmap
+        .iter_from(std::cmp::max(after.unwrap_or(now), now))
+        .filter(|(_start, slot)| slot.can_book(0))
+        .map(|(start, slot)| (start, slot.availability()));
+
+      CursorPage::from_iter(available, self.available_pickup_slots_count, limit)
+    }
+
+    /**
+   * Writes the pickup slot and keeps the count of slots with free capacity in step with it.
+   */
+    fn save_pickup_slot(&mut self, slot: &PickupSlot) {
+      let was_available: bool = self.pickup_slots_tmap
+        .insert(&slot.window.start, slot)
+        .is_some_and(|previous| previous.can_book(0));
+
+      match (was_available, slot.can_book(0)) {
+        (false, true) => self.available_pickup_slots_count += 1,
+        (true, false) => self.available_pickup_slots_count -= 1,
+        _ => {}
+      }
+    }
+
+    /**
+   * Books an order of the given weight into the pickup slot matching the given window.
+   */
+    pub(crate) fn book_pickup_slot(&mut self, window: &TimeWindow, weight_in_grams: u32) {
+      let mut slot: PickupSlot = match self.pickup_slots_tmap.get(&window.start) {
+        Some(slot) => slot,
+        None => env::panic_str("Pickup window must match an available pickup slot.")
+      };
+
+      require!(slot.window == *window, "Pickup window must match an available pickup slot.");
+      require!(slot.can_book(weight_in_grams), "Pickup slot is full.");
+
+      slot.booked_orders += 1;
+      slot.booked_weight_in_grams += weight_in_grams;
+
+      self.save_pickup_slot(&slot);
+    }
+
+    /**
+   * Frees the capacity an order of the given weight held in the pickup slot matching the given window.
+   */
+    pub(crate) fn release_pickup_slot(&mut self, window: &TimeWindow, weight_in_grams: u32) {
+      if let Some(mut slot) = self.pickup_slots_tmap.get(&window.start) {
+        slot.booked_orders = slot.booked_orders.saturating_sub(1);
+        slot.booked_weight_in_grams = slot.booked_weight_in_grams.saturating_sub(weight_in_grams);
+
+        self.save_pickup_slot(&slot);
+      }
+    }
+}
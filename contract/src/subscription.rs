@@ -0,0 +1,327 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::{NewOrderItem, Order, OrderItem, TimeWindow};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, AccountId, Timestamp, env, log, require, Promise};
+use near_sdk::json_types::U128;
+
+pub const MIN_SUBSCRIPTION_CADENCE: u64 = 24 * 60 * 60 * 1_000_000_000;
+pub const SUBSCRIPTION_ORDER_LEAD_TIME: u64 = 24 * 60 * 60 * 1_000_000_000;
+pub const MAX_SUBSCRIPTIONS_PER_CRANK: u64 = 20;
+
+/**
+ * all available subscription statuses
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone, Copy)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SubscriptionStatus {
+  Active = 1,
+  Paused,
+}
+
+/**
+ * a recurring order of a customer, paid from a prepaid balance
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Subscription {
+  pub customer_id: AccountId,
+  pub description: String,
+  pub items: Vec<NewOrderItem>,
+  pub cadence: u64,
+  pub pickup_window: TimeWindow,
+  pub delivery_window: TimeWindow,
+  pub balance_in_yocto_near: U128,
+  pub status: SubscriptionStatus,
+  pub last_order_id: Option<String>,
+  pub created: Timestamp,
+  pub updated: Timestamp,
+}
+
+impl Subscription {
+  /**
+   * Moves the pickup and delivery windows on by one cadence.
+   */
+  pub fn advance(&mut self) {
+    self.pickup_window = TimeWindow { start: self.pickup_window.start + self.cadence, end: self.pickup_window.end + self.cadence };
+    self.delivery_window = TimeWindow { start: self.delivery_window.start + self.cadence, end: self.delivery_window.end + self.cadence };
+  }
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Customer subscribes to a recurring order. The attached deposit, less storage cost, becomes the prepaid balance.
+   * The cadence is in nanoseconds and the windows are those of the first order.
+   * @return newly created Subscription object for the calling customer.
+   */
+    #[payable]
+    pub fn create_subscription(
+      &mut self,
+      description: String,
+      items: Vec<NewOrderItem>,
+      cadence: u64,
+      pickup_window: TimeWindow,
+      delivery_window: TimeWindow
+    ) -> Subscription {
+      let customer_id: AccountId = env::predecessor_account_id();
+
+      require!(self.check_customer_exists(&customer_id), "Customer does not exists.");
+      require!(self.subscriptions_lookup.get(&customer_id).is_none(), "Subscription already exists.");
+      require!(cadence >= MIN_SUBSCRIPTION_CADENCE, "Subscription cadence must be at least one day.");
+
+      TimeWindow::assert_valid_schedule(&pickup_window, &delivery_window);
+
+      self.price_order_items(&items);
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      let mut subscription = Subscription {
+        customer_id: customer_id.clone(),
+        description,
+        items,
+        cadence,
+        pickup_window,
+        delivery_window,
+        balance_in_yocto_near: U128(0),
+        status: SubscriptionStatus::Active,
+        last_order_id: None,
+        created: env::block_timestamp(),
+        updated: env::block_timestamp(),
+      };
+
+      self.subscriptions_lookup.insert(&customer_id, &subscription);
+      self.subscription_queue_tmap.insert(&(pickup_window.start, customer_id.clone()), &());
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+
+      require!(deposit_amount > final_storage_cost, "Deposited amount must cover the storage cost.");
+
+      subscription.balance_in_yocto_near = U128(deposit_amount - final_storage_cost);
+
+      self.subscriptions_lookup.insert(&customer_id, &subscription);
+
+      log!("created subscription for customer: {} successfully", &customer_id);
+
+      subscription
+    }
+
+    /**
+   * Customer adds the attached deposit to the prepaid balance of their subscription.
+   * @return updated Subscription object for the calling customer.
+   */
+    #[payable]
+    pub fn fund_subscription(&mut self) -> Subscription {
+      let customer_id: AccountId = env::predecessor_account_id();
+      let mut subscription: Subscription = self.get_subscription(customer_id.clone());
+
+      require!(env::attached_deposit() > 0, "Deposited amount must be greater than 0.");
+
+      subscription.balance_in_yocto_near = U128(u128::checked_add(subscription.balance_in_yocto_near.0, env::attached_deposit()).unwrap());
+      subscription.updated = env::block_timestamp();
+
+      self.subscriptions_lookup.insert(&customer_id, &subscription);
+
+      log!("funded subscription of customer: {} successfully", &customer_id);
+
+      subscription
+    }
+
+    /**
+   * Customer pauses their subscription; no orders are created until it is resumed.
+   * @return updated Subscription object for the calling customer.
+   */
+    pub fn pause_subscription(&mut self) -> Subscription {
+      let customer_id: AccountId = env::predecessor_account_id();
+      let mut subscription: Subscription = self.get_subscription(customer_id.clone());
+
+      require!(subscription.status == SubscriptionStatus::Active, "Subscription must have Active status.");
+
+      self.subscription_queue_tmap.remove(&(subscription.pickup_window.start, customer_id.clone()));
+
+      subscription.status = SubscriptionStatus::Paused;
+      subscription.updated = env::block_timestamp();
+
+      self.subscriptions_lookup.insert(&customer_id, &subscription);
+
+      log!("paused subscription of customer: {} successfully", &customer_id);
+
+      subscription
+    }
+
+    /**
+   * Customer resumes their paused subscription with the windows of the next order.
+   * @return updated Subscription object for the calling customer.
+   */
+    pub fn resume_subscription(&mut self, pickup_window: TimeWindow, delivery_window: TimeWindow) -> Subscription {
+      let customer_id: AccountId = env::predecessor_account_id();
+      let mut subscription: Subscription = self.get_subscription(customer_id.clone());
+
+      require!(subscription.status == SubscriptionStatus::Paused, "Subscription must have Paused status.");
+
+      TimeWindow::assert_valid_schedule(&pickup_window, &delivery_window);
+
+      subscription.pickup_window = pickup_window;
+      subscription.delivery_window = delivery_window;
+      subscription.status = SubscriptionStatus::Active;
+      subscription.updated = env::block_timestamp();
+
+      self.subscriptions_lookup.insert(&customer_id, &subscription);
+      self.subscription_queue_tmap.insert(&(pickup_window.start, customer_id.clone()), &());
+
+      log!("resumed subscription of customer: {} successfully", &customer_id);
+
+      subscription
+    }
+
+    /**
+   * Customer cancels their subscription and gets the remaining balance back along with the released storage.
+   * @return cancelled Subscription object for the calling customer.
+   */
+    pub fn cancel_subscription(&mut self) -> Subscription {
+      let customer_id: AccountId = env::predecessor_account_id();
+      let subscription: Subscription = self.get_subscription(customer_id.clone());
+
+      let storage_used_before: u64 = env::storage_usage();
+
+      self.subscription_queue_tmap.remove(&(subscription.pickup_window.start, customer_id.clone()));
+      self.subscriptions_lookup.remove(&customer_id);
+
+      log!("cancelled subscription of customer: {} successfully", &customer_id);
+
+      let refund = u128::checked_add(subscription.balance_in_yocto_near.0, self.calculate_storage_refund(storage_used_before)).unwrap();
+
+      if refund > 0 {
+        Promise::new(customer_id.clone()).transfer(refund);
+        log!("Transferred refund: {} yN to account_id: {}", &refund, &customer_id);
+      }
+
+      subscription
+    }
+
+    /**
+   * Only the subscribed customer or an admin can fetch the subscription.
+   * @return Subscription object for the given account ID.
+   */
+    pub fn get_subscription(&self, customer_id: AccountId) -> Subscription  {
+      let caller: AccountId = env::predecessor_account_id();
+
+      require!(caller == customer_id || self.check_is_admin(&caller), "You can only fetch your own subscription.");
+
+      match self.subscriptions_lookup.get(&customer_id) {
+        Some(subscription) => subscription,
+        None => env::panic_str("Subscription does not exist.")
+      }
+    }
+
+    /**
+   * Anyone can call this function.
+   * Creates the orders of active subscriptions whose next pickup starts within the order lead time.
+   * Missed pickups are skipped; subscriptions whose balance, services or pickup slot can not cover the order are paused.
+   * @return IDs of the newly created orders.
+   */
+    pub fn process_due_subscriptions(&mut self, limit: Option<u64>) -> Vec<String> {
+      let now: Timestamp = env::block_timestamp();
+      let due_until: Timestamp = now + SUBSCRIPTION_ORDER_LEAD_TIME;
+
+      let due: Vec<(Timestamp, AccountId)> = self.subscription_queue_tmap
+        .iter()
+        .take_while(|((start, _customer_id), _)| *start <= due_until)
+        .take(std::cmp::min(limit.unwrap_or(MAX_SUBSCRIPTIONS_PER_CRANK), MAX_SUBSCRIPTIONS_PER_CRANK) as usize)
+        .map(|(key, _)| key)
+        .collect();
+
+      let mut order_ids: Vec<String> = Vec::new();
+
+      for key in due {
+        self.subscription_queue_tmap.remove(&key);
+
+        let mut subscription: Subscription = self.subscriptions_lookup.get(&key.1).unwrap();
+
+        while subscription.pickup_window.start <= now {
+          log!("skipped missed pickup of subscription: {}", &subscription.customer_id);
+          subscription.advance();
+        }
+
+        if subscription.pickup_window.start <= due_until {
+          match self.place_subscription_order(&mut subscription) {
+            Some(order) => order_ids.push(order.id),
+            None => subscription.status = SubscriptionStatus::Paused,
+          }
+        }
+
+        subscription.updated = now;
+
+        self.subscriptions_lookup.insert(&subscription.customer_id, &subscription);
+
+        if subscription.status == SubscriptionStatus::Active {
+          self.subscription_queue_tmap.insert(&(subscription.pickup_window.start, subscription.customer_id.clone()), &());
+        }
+      }
+
+      log!("processed {} due subscriptions successfully", order_ids.len());
+
+      order_ids
+    }
+
+    /**
+   * Creates the next order of the given subscription and charges its price and storage cost to the balance.
+   * @return newly created Order object, or None if the subscription can not cover the order.
+   */
+    fn place_subscription_order(&mut self, subscription: &mut Subscription) -> Option<Order> {
+      let services_available: bool = subscription.items.iter().all(|item| {
+        self.services_umap.get(&item.service_id).is_some_and(|service| service.is_active)
+      });
+
+      if !services_available {
+        log!("paused subscription: {}, a service is not available", &subscription.customer_id);
+        return None;
+      }
+
+      let order_items: Vec<OrderItem> = self.price_order_items(&subscription.items);
+      let price_in_yocto_near: u128 = OrderItem::total_price(&order_items);
+      let weight_in_grams: u32 = OrderItem::total_weight(&order_items);
+
+      let slot_available: bool = self.pickup_slots_tmap.get(&subscription.pickup_window.start).is_some_and(|slot| {
+        slot.window == subscription.pickup_window && slot.can_book(weight_in_grams)
+      });
+
+      if !slot_available {
+        log!("paused subscription: {}, the pickup slot is not available", &subscription.customer_id);
+        return None;
+      }
+
+      if subscription.balance_in_yocto_near.0 < price_in_yocto_near + ONE_YOCTO_NEAR {
+        log!("paused subscription: {}, the balance is too low", &subscription.customer_id);
+        return None;
+      }
+
+      let storage_used_before: u64 = env::storage_usage();
+
+      self.book_pickup_slot(&subscription.pickup_window, weight_in_grams);
+
+      let order: Order = self.place_order(
+        &subscription.customer_id,
+        &subscription.description,
+        &order_items,
+        &subscription.pickup_window,
+        &subscription.delivery_window,
+      );
+
+      let final_cost = u128::checked_add(price_in_yocto_near, self.calculate_storage_cost(storage_used_before)).unwrap();
+      log!("final_cost: {} yN", final_cost);
+
+      subscription.balance_in_yocto_near = U128(u128::checked_sub(subscription.balance_in_yocto_near.0, final_cost).unwrap());
+      subscription.last_order_id = Some(order.id.clone());
+      subscription.advance();
+
+      Some(order)
+    }
+}
@@ -0,0 +1,370 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::ONE_YOCTO_NEAR;
+use crate::char_count;
+use crate::{NewOrderItem, Order, OrderItem, OrderStatus, PaymentType, TimeWindow};
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Serialize, Deserialize};
+use near_sdk::{near_bindgen, ext_contract, is_promise_success, AccountId, PromiseOrValue, env, log, require, Promise};
+use near_sdk::json_types::U128;
+
+pub const MAX_TOKEN_SYMBOL_LENGTH: usize = 12;
+
+#[allow(clippy::all)]
+mod u256 {
+  uint::construct_uint! {
+    /// 256-bit unsigned integer used to convert between yoctoNEAR and token units without overflow.
+    pub struct U256(4);
+  }
+}
+
+use u256::U256;
+
+/**
+ * the part of the NEP-141 fungible token interface the contract calls
+ **/
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+  fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+}
+
+/**
+ * a NEP-141 token accepted as payment, with the number of its smallest units that one NEAR is worth
+ **/
+#[derive(Deserialize, Serialize, BorshDeserialize, BorshSerialize, PartialEq, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct AcceptedToken {
+  pub token_id: AccountId,
+  pub symbol: String,
+  pub units_per_near: U128,
+}
+
+impl AcceptedToken {
+  /**
+   * Converts an amount in yoctoNEAR into the smallest units of this token, rounding down.
+   * @return amount in token units.
+   */
+  pub fn to_token_amount(&self, amount_in_yocto_near: u128) -> u128 {
+    let amount: U256 = U256::from(amount_in_yocto_near) * U256::from(self.units_per_near.0) / U256::from(ONE_YOCTO_NEAR);
+
+    amount.as_u128()
+  }
+}
+
+/**
+ * the action a customer asks for in the msg of ft_transfer_call
+ **/
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum TokenPaymentMessage {
+  CreateOrder {
+    description: String,
+    items: Vec<NewOrderItem>,
+    pickup_window: TimeWindow,
+    delivery_window: TimeWindow,
+  },
+  TopUpOrder {
+    order_id: String,
+  },
+}
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Accepts the given token as payment, or updates its symbol and rate.
+   * @return AcceptedToken object for the given token ID.
+   */
+    pub fn add_accepted_token(&mut self, token_id: AccountId, symbol: String, units_per_near: U128) -> AcceptedToken {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can manage accepted tokens.");
+      require!(!symbol.is_empty() && char_count(&symbol) <= MAX_TOKEN_SYMBOL_LENGTH, "Token symbol must be between 1 and 12 characters.");
+      require!(units_per_near.0 > 0, "Token rate must be greater than 0.");
+
+      let token = AcceptedToken {
+        token_id: token_id.clone(),
+        symbol,
+        units_per_near,
+      };
+
+      self.accepted_tokens_umap.insert(&token_id, &token);
+
+      log!("accepted token: {} successfully", &token_id);
+
+      token
+    }
+
+    /**
+   * Only the admin can call this function.
+   * Stops accepting the given token for new payments. Orders already paid in it are still refunded and settled in it.
+   */
+    pub fn remove_accepted_token(&mut self, token_id: AccountId) {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can manage accepted tokens.");
+      require!(self.accepted_tokens_umap.remove(&token_id).is_some(), "Token is not accepted.");
+
+      log!("removed accepted token: {} successfully", &token_id);
+    }
+
+    /**
+   * Deposits the attached NEAR into the token storage balance of the caller, which pays for the storage of their token orders.
+   * @return token storage balance of the caller in yoctoNEAR.
+   */
+    #[payable]
+    pub fn deposit_token_storage(&mut self) -> U128 {
+      let account_id: AccountId = env::predecessor_account_id();
+
+      require!(env::attached_deposit() > 0, "Deposited amount must be greater than 0.");
+
+      let balance: u128 = u128::checked_add(self.token_storage_balance(&account_id), env::attached_deposit()).unwrap();
+
+      self.token_storage_deposits_lookup.insert(&account_id, &balance);
+
+      log!("deposited token storage: {} yN for account_id: {}", env::attached_deposit(), &account_id);
+
+      U128(balance)
+    }
+
+    /**
+   * Withdraws the whole token storage balance of the caller.
+   * @return withdrawn amount in yoctoNEAR.
+   */
+    pub fn withdraw_token_storage(&mut self) -> U128 {
+      let account_id: AccountId = env::predecessor_account_id();
+      let balance: u128 = self.token_storage_deposits_lookup.remove(&account_id).unwrap_or(0);
+
+      require!(balance > 0, "Token storage balance is empty.");
+
+      Promise::new(account_id.clone()).transfer(balance);
+      log!("Transferred storage: {} yN to account_id: {}", &balance, &account_id);
+
+      U128(balance)
+    }
+
+    /**
+   * Gets the token storage balance of the given account.
+   * @return token storage balance in yoctoNEAR.
+   */
+    pub fn get_token_storage_balance(&self, account_id: AccountId) -> U128 {
+      U128(self.token_storage_balance(&account_id))
+    }
+
+    /**
+   * Gets the tokens owed to the given account whose transfer failed.
+   * @return amount in token units.
+   */
+    pub fn get_unclaimed_tokens(&self, account_id: AccountId, token_id: AccountId) -> U128 {
+      U128(self.unclaimed_tokens_lookup.get(&(account_id, token_id)).unwrap_or(0))
+    }
+
+    /**
+   * Retries the failed transfers of the given token to the caller.
+   * @return amount in token units sent again.
+   */
+    pub fn claim_tokens(&mut self, token_id: AccountId) -> U128 {
+      let account_id: AccountId = env::predecessor_account_id();
+      let amount: u128 = self.unclaimed_tokens_lookup.remove(&(account_id.clone(), token_id.clone())).unwrap_or(0);
+
+      require!(amount > 0, "No unclaimed tokens.");
+
+      self.transfer_tokens(&token_id, &account_id, amount, "claim of unclaimed tokens".to_string());
+      log!("Transferred claim: {} of token: {} to account_id: {}", &amount, &token_id, &account_id);
+
+      U128(amount)
+    }
+
+    /**
+   * Callback of ft_transfer. Records a failed transfer so the receiver can claim the tokens later.
+   * @return whether the transfer succeeded.
+   */
+    #[private]
+    pub fn resolve_token_transfer(&mut self, receiver_id: AccountId, token_id: AccountId, amount: U128) -> bool {
+      if is_promise_success() {
+        return true;
+      }
+
+      let key: (AccountId, AccountId) = (receiver_id, token_id);
+      let unclaimed: u128 = self.unclaimed_tokens_lookup.get(&key).unwrap_or(0);
+
+      self.unclaimed_tokens_lookup.insert(&key, &u128::checked_add(unclaimed, amount.0).unwrap());
+
+      log!("failed transfer: {} of token: {} to account_id: {} recorded", amount.0, &key.1, &key.0);
+
+      false
+    }
+
+    /**
+   * Gets all tokens accepted as payment.
+   * @return list of AcceptedToken objects.
+   */
+    pub fn get_accepted_tokens(&self) -> Vec<AcceptedToken> {
+      self.accepted_tokens_umap.values().collect()
+    }
+
+    /**
+   * NEP-141 receiver. Creates or tops up an order of the sender with tokens sent through ft_transfer_call.
+   * The price is converted at the token rate, which is locked into a new order; storage is charged to the sender's token storage balance in NEAR.
+   * @return the unused amount, which the token contract refunds to the sender.
+   */
+    pub fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128> {
+      let token: AcceptedToken = match self.accepted_tokens_umap.get(&env::predecessor_account_id()) {
+        Some(token) => token,
+        None => env::panic_str("Token is not accepted."),
+      };
+
+      let message: TokenPaymentMessage = match near_sdk::serde_json::from_str(&msg) {
+        Ok(message) => message,
+        Err(_) => env::panic_str("Payment message is invalid."),
+      };
+
+      let charged: u128 = match message {
+        TokenPaymentMessage::CreateOrder { description, items, pickup_window, delivery_window } => {
+          self.create_token_order(&sender_id, &token, amount.0, description, items, pickup_window, delivery_window)
+        },
+        TokenPaymentMessage::TopUpOrder { order_id } => {
+          self.top_up_token_order(&sender_id, &token, amount.0, order_id)
+        },
+      };
+
+      PromiseOrValue::Value(U128(amount.0 - charged))
+    }
+
+    /**
+   * Creates an order for the sender paid with the transferred tokens.
+   * @return amount of tokens charged.
+   */
+    #[allow(clippy::too_many_arguments)]
+    fn create_token_order(
+      &mut self,
+      customer_id: &AccountId,
+      token: &AcceptedToken,
+      amount: u128,
+      description: String,
+      items: Vec<NewOrderItem>,
+      pickup_window: TimeWindow,
+      delivery_window: TimeWindow
+    ) -> u128 {
+      require!(self.check_customer_exists(customer_id), "Customer does not exists.");
+
+      TimeWindow::assert_valid_schedule(&pickup_window, &delivery_window);
+
+      let order_items: Vec<OrderItem> = self.price_order_items(&items);
+      let price: u128 = token.to_token_amount(OrderItem::total_price(&order_items));
+      log!("price: {} {}", price, &token.symbol);
+
+      require!(amount >= price, "Transferred amount must cover the order price.");
+
+      let storage_used_before: u64 = env::storage_usage();
+
+      self.book_pickup_slot(&pickup_window, OrderItem::total_weight(&order_items));
+
+      let mut order: Order = self.place_order(customer_id, &description, &order_items, &pickup_window, &delivery_window);
+
+      order.payment_type = PaymentType::FungibleToken;
+      order.payment_token = Some(token.clone());
+
+      self.save_order(&order);
+
+      self.charge_token_storage(customer_id, storage_used_before);
+
+      price
+    }
+
+    /**
+   * Pays the amount due of the sender's re-measured order with the transferred tokens.
+   * @return amount of tokens charged.
+   */
+    fn top_up_token_order(&mut self, customer_id: &AccountId, token: &AcceptedToken, amount: u128, order_id: String) -> u128 {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(customer_id == &order.customer_id, "You can only top up your own order.");
+      require!(order.status == OrderStatus::AwaitingTopUp, "Order must have AwaitingTopUp status.");
+      require!(order.payment_token.as_ref().is_some_and(|order_token| order_token.token_id == token.token_id), "Order is not paid in this token.");
+
+      let amount_due: u128 = order.payment_token.as_ref().unwrap().to_token_amount(order.amount_due_in_yocto_near.0);
+
+      require!(amount >= amount_due, "Transferred amount must cover the amount due.");
+
+      let storage_used_before: u64 = env::storage_usage();
+
+      order.amount_due_in_yocto_near = U128(0);
+
+      self.set_order_status(&mut order, OrderStatus::PickedUp, None);
+
+      self.charge_token_storage(customer_id, storage_used_before);
+
+      log!("topped up order: {} successfully", &order_id);
+
+      amount_due
+    }
+
+    /**
+   * Pays out part of the escrowed price of the given order, in NEAR or in the token the order was paid with.
+   */
+    pub(crate) fn transfer_order_funds(&self, order: &Order, receiver_id: &AccountId, amount_in_yocto_near: u128, kind: &str) {
+      match &order.payment_token {
+        Some(token) => {
+          let amount: u128 = token.to_token_amount(amount_in_yocto_near);
+
+          if amount > 0 {
+            self.transfer_tokens(&token.token_id, receiver_id, amount, format!("{} for order {}", kind, &order.id));
+            log!("Transferred {}: {} {} to account_id: {}", kind, &amount, &token.symbol, receiver_id);
+          }
+        },
+        None => {
+          Promise::new(receiver_id.clone()).transfer(amount_in_yocto_near);
+          log!("Transferred {}: {} yN to account_id: {}", kind, &amount_in_yocto_near, receiver_id);
+        },
+      }
+    }
+
+    /**
+   * Sends tokens through ft_transfer, recording the amount for the receiver to claim if the transfer fails.
+   */
+    fn transfer_tokens(&self, token_id: &AccountId, receiver_id: &AccountId, amount: u128, memo: String) {
+      ext_ft::ext(token_id.clone())
+        .with_attached_deposit(1)
+        .ft_transfer(receiver_id.clone(), U128(amount), Some(memo))
+        .then(Self::ext(env::current_account_id()).resolve_token_transfer(receiver_id.clone(), token_id.clone(), U128(amount)));
+    }
+
+    /**
+   * Charges the storage used since storage_used_before to the token storage balance of the given customer.
+   */
+    fn charge_token_storage(&mut self, customer_id: &AccountId, storage_used_before: u64) {
+      let final_storage_cost: u128 = self.calculate_storage_cost(storage_used_before);
+      let balance: u128 = self.token_storage_balance(customer_id);
+
+      require!(balance >= final_storage_cost, "Token storage balance must cover the storage of the order.");
+
+      self.token_storage_deposits_lookup.insert(customer_id, &(balance - final_storage_cost));
+    }
+
+    /**
+   * Pays the storage used since storage_used_before by a change to the given order.
+   * Token orders charge it to the token storage balance of the customer, so their token refunds are not reduced.
+   * @return storage cost to take out of a NEAR refund.
+   */
+    pub(crate) fn charge_order_storage(&mut self, order: &Order, storage_used_before: u64) -> u128 {
+      match order.payment_token {
+        Some(_) => {
+          self.charge_token_storage(&order.customer_id, storage_used_before);
+
+          0
+        },
+        None => self.calculate_storage_cost(storage_used_before),
+      }
+    }
+
+    /**
+   * Gets the token storage balance of the given account.
+   * @return token storage balance in yoctoNEAR.
+   */
+    fn token_storage_balance(&self, account_id: &AccountId) -> u128 {
+      self.token_storage_deposits_lookup.get(account_id).unwrap_or(0)
+    }
+}
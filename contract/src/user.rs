@@ -31,8 +31,9 @@ impl User {
    * Creates a user for the given account ID.
    * @return User object for the given ID.
   */
+  #[allow(clippy::too_many_arguments)]
   fn new(account_id: &AccountId, name: &String, full_address: &String, landmark: &String, google_plus_code_address: &String, phone: &String, email: &String) -> User {
-    let customer = User {
+    User {
         id: account_id.to_owned(),
         name: name.to_owned(),
         full_address: full_address.to_owned(),
@@ -43,9 +44,7 @@ impl User {
         role: UserRole::Customer,
         created: env::block_timestamp(),
         updated: env::block_timestamp(),
-    };
-
-    customer
+    }
   }
 }
 
@@ -58,17 +57,18 @@ impl Contract {
    * @return newly created Customer object for the given ID.
    */
     #[payable]
+    #[allow(clippy::too_many_arguments)]
     pub fn create_customer(&mut self, account_id: AccountId, name: String, phone: String, email: String, full_address: String, landmark: String, google_plus_code_address: String, ) -> User {
-      require!(&env::predecessor_account_id().to_string() == &account_id.to_string(), "You can only register your own account.");
+      require!(env::predecessor_account_id().to_string() == account_id.to_string(), "You can only register your own account.");
 
       let deposit_amount: u128 = env::attached_deposit();
       let storage_used_before: u64 = env::storage_usage();
 
-      require!(&deposit_amount >= &ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(deposit_amount >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
 
       let exists = self.check_customer_exists(&account_id);
 
-      if exists == true {
+      if exists {
         env::panic_str("Account already exists.")
       }
 
@@ -86,11 +86,11 @@ impl Contract {
 
       log!("created customer: {} successfully", &account_id);
 
-      let final_storage_cost = self.calculate_storage_cost(storage_used_before.clone());
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
 
       let surplus = u128::checked_sub(deposit_amount, final_storage_cost).unwrap();
 
-      if &surplus > &0 {
+      if surplus > 0 {
         Promise::new(env::predecessor_account_id().clone()).transfer(surplus);
         log!("Transferred surplus: {} yN to account_id: {}", surplus, &env::predecessor_account_id());
       }
@@ -103,19 +103,20 @@ impl Contract {
    * @return updated Customer object for the given ID.
    */
     #[payable]
+    #[allow(clippy::too_many_arguments)]
     pub fn update_customer(&mut self, account_id: AccountId, name: String, phone: String, email: String, full_address: String, landmark: String, google_plus_code_address: String, ) -> User {
       require!(env::predecessor_account_id().to_string() == account_id.to_string(), "You can only update your own account.");
 
       let exists = self.check_customer_exists(&account_id);
 
-      if exists == false {
+      if !exists {
         env::panic_str("Account does not exist.")
       }
 
       let deposit_amount: u128 = env::attached_deposit();
       let storage_used_before: u64 = env::storage_usage();
 
-      require!(&deposit_amount >= &ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
+      require!(deposit_amount >= ONE_YOCTO_NEAR, "Deposited amount must be at least 1 Near.");
 
       let mut customer = self.get_customer_by_account_id(account_id.clone());
 
@@ -134,7 +135,7 @@ impl Contract {
 
       let surplus = u128::checked_sub(deposit_amount, final_storage_cost).unwrap();
 
-      if &surplus > &0 {
+      if surplus > 0 {
         Promise::new(env::predecessor_account_id().clone()).transfer(surplus);
         log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
       }
@@ -156,7 +157,7 @@ impl Contract {
      *  @return an Customer object for the given ID.
     */
     pub fn get_customer_by_account_id(&self, account_id: AccountId) -> User  {
-      require!(&env::predecessor_account_id().to_string() == &account_id.to_string(), "You can only fetch your own account.");
+      require!(env::predecessor_account_id().to_string() == account_id.to_string(), "You can only fetch your own account.");
 
       let result = self.customers_umap.get(&account_id);
 
@@ -0,0 +1,108 @@
+use crate::Contract;
+use crate::ContractExt;
+
+use crate::{Order, OrderItem, OrderStatus, PaymentType, Service};
+
+use near_sdk::{near_bindgen, env, log, require, Promise};
+use near_sdk::json_types::U128;
+
+// Implement the contract structure
+#[near_bindgen]
+impl Contract {
+
+    /**
+   * Only the admin can call this function.
+   * Records the measured weight of each line item and reprices the order from the catalog.
+   * A lower price is refunded to the customer right away; a higher price puts the order in AwaitingTopUp status,
+   * except for cash-on-delivery orders, which stay due until the admin confirms the cash payment.
+   * @return updated Order object for the given order ID.
+   */
+    pub fn record_order_weight(&mut self, order_id: String, weights_in_grams: Vec<u32>) -> Order {
+      require!(self.check_is_admin(&env::predecessor_account_id()), "Only admin can record order weight.");
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(order.status == OrderStatus::PickedUp || order.status == OrderStatus::AwaitingTopUp, "Order must have PickedUp or AwaitingTopUp status.");
+      require!(weights_in_grams.len() == order.items.len(), "Order must have one measured weight per item.");
+
+      let paid: u128 = order.paid_in_yocto_near();
+      let credited: u128 = order.credited_in_yocto_near();
+
+      for (item, weight_in_grams) in order.items.iter_mut().zip(weights_in_grams) {
+        require!(item.quantity > 0 || weight_in_grams > 0, "Order item must have a quantity or a weight.");
+
+        let service: Service = self.get_service_by_id(item.service_id.clone());
+
+        item.weight_in_grams = weight_in_grams;
+        item.price_in_yocto_near = U128(service.calculate_price(weight_in_grams, item.quantity));
+      }
+
+      order.weight_in_grams = OrderItem::total_weight(&order.items);
+      order.price_in_yocto_near = U128(OrderItem::total_price(&order.items));
+
+      let refund: u128 = std::cmp::min(credited.saturating_sub(order.price_in_yocto_near.0), paid);
+      order.amount_due_in_yocto_near = U128(order.price_in_yocto_near.0.saturating_sub(credited));
+      order.cash_paid_in_yocto_near = U128(std::cmp::min(order.cash_paid_in_yocto_near.0, order.price_in_yocto_near.0 - order.amount_due_in_yocto_near.0));
+
+      let reason: Option<String> = Some(format!("Measured weight: {} grams", order.weight_in_grams));
+
+      if order.amount_due_in_yocto_near.0 > 0 && order.status == OrderStatus::PickedUp && order.payment_type != PaymentType::CashOnDelivery {
+        self.set_order_status(&mut order, OrderStatus::AwaitingTopUp, reason);
+      } else if order.amount_due_in_yocto_near.0 == 0 && order.status == OrderStatus::AwaitingTopUp {
+        self.set_order_status(&mut order, OrderStatus::PickedUp, reason);
+      } else {
+        self.save_order(&order);
+      }
+
+      log!("recorded weight of order: {} successfully", &order_id);
+
+      if refund > 0 {
+        self.transfer_order_funds(&order, &order.customer_id, refund, "refund");
+      }
+
+      order
+    }
+
+    /**
+   * Customer pays the amount due after their order was re-measured, which returns it to PickedUp status.
+   * @return updated Order object for the given order ID.
+   */
+    #[payable]
+    pub fn top_up_order(&mut self, order_id: String) -> Order {
+      require!(self.check_order_exists(&order_id), "Order does not exists.");
+
+      let deposit_amount: u128 = env::attached_deposit();
+      let storage_used_before: u64 = env::storage_usage();
+
+      let mut order: Order = self.orders_umap.get(&order_id).unwrap();
+
+      require!(env::predecessor_account_id() == order.customer_id, "You can only top up your own order.");
+      require!(order.status == OrderStatus::AwaitingTopUp, "Order must have AwaitingTopUp status.");
+      require!(order.payment_token.is_none(), "Orders paid in tokens are topped up with ft_transfer_call.");
+
+      let amount_due: u128 = order.amount_due_in_yocto_near.0;
+
+      order.amount_due_in_yocto_near = U128(0);
+
+      self.set_order_status(&mut order, OrderStatus::PickedUp, None);
+
+      log!("topped up order: {} successfully", &order_id);
+
+      let final_storage_cost = self.calculate_storage_cost(storage_used_before);
+
+      let final_cost = u128::checked_add(amount_due, final_storage_cost).unwrap();
+      log!("final_cost: {} yN", final_cost);
+
+      require!(deposit_amount >= final_cost, "Deposited amount must cover the amount due and storage cost.");
+
+      let surplus = deposit_amount - final_cost;
+
+      if surplus > 0 {
+        Promise::new(env::predecessor_account_id()).transfer(surplus);
+        log!("Transferred surplus: {} yN to account_id: {}", &surplus, &env::predecessor_account_id());
+      }
+
+      order
+    }
+}
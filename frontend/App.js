@@ -11,6 +11,7 @@ import OrderForm from "./components/OrderForm";
 import Feedback from "./components/Feedback";
 import ProjectDemo from "./components/ProjectDemo";
 import UserForm from "./components/UserForm";
+import Reviews from "./components/Reviews";
 
 import "./assets/global.css";
 
@@ -30,7 +31,7 @@ export default function App({ isSignedIn, accountId, contract, wallet }) {
       setLoader(true);
       const result =
         accountId && (await contract.get_orders_by_customer_id(accountId));
-      setOrders(result);
+      setOrders(result?.items);
     } catch (error) {
       setLoader(false);
       console.error(`[fetchOrdersForCustomer] ${error?.message}`);
@@ -60,7 +61,7 @@ export default function App({ isSignedIn, accountId, contract, wallet }) {
     try {
       setLoader(true);
       const result = accountId && (await contract.get_order_list());
-      setOrders(result);
+      setOrders(result?.items);
     } catch (error) {
       setLoader(false);
       console.error(`[fetchOrdersForAdmin] ${error?.message}`);
@@ -107,6 +108,7 @@ export default function App({ isSignedIn, accountId, contract, wallet }) {
         <Routes path="/" element={<Home />}>
           <Route path="account" element={<UserForm />} />
           <Route path="about" element={<ProjectDemo />} />
+          <Route path="reviews" element={<Reviews />} />
           <Route path="new" element={<OrderForm />} />
           <Route path=":orderId" element={<Feedback />} />
           <Route path="*" element={<Home />} />
@@ -27,10 +27,11 @@ export default function Feedback() {
     price_in_yocto_near: "0",
     customer_feedback: "None",
     customer_feedback_comment: "",
+    rating: null,
   });
 
   const [feedbackRating, setFeedbackRating] = useState(
-    order?.customer_feedback || "None"
+    order?.rating || { wash_quality: 5, punctuality: 5, staff_behaviour: 5 }
   );
   const [feedbackComment, setFeedbackComment] = useState(
     order?.customer_feedback_comment || ""
@@ -55,20 +56,21 @@ export default function Feedback() {
         return;
       }
 
-      const { inputRating, inputFeedbackComment } = e.target.elements;
+      const { inputFeedbackComment } = e.target.elements;
 
       try {
         setLoader(true);
 
-        await contract.submit_feedback(
+        const updatedOrder = await contract.submit_feedback(
           orderId,
-          inputRating?.value,
+          feedbackRating,
           inputFeedbackComment?.value
         );
 
         const updatedOrders = orders.map((o) => {
           if (o?.id === orderId) {
-            o.customer_feedback = inputRating?.value;
+            o.rating = updatedOrder?.rating;
+            o.customer_feedback = updatedOrder?.customer_feedback;
             o.customer_feedback_comment = inputFeedbackComment?.value;
           }
           return o;
@@ -96,7 +98,9 @@ export default function Feedback() {
 
         if (found) {
           setOrder(Object.assign({}, order, found));
-          setFeedbackRating(found?.customer_feedback);
+          if (found?.rating) {
+            setFeedbackRating(found.rating);
+          }
           setFeedbackComment(found?.customer_feedback_comment);
         }
         console.log(found);
@@ -224,29 +228,37 @@ export default function Feedback() {
               <form
                 onSubmit={(e) => loader == false && !isAdmin && handleSubmit(e)}
               >
-                <div className="mb-3">
-                  <label htmlFor="inputRating" className="form-label">
-                    Rating
-                  </label>
-                  <select
-                    id="inputRating"
-                    className="form-select form-select-sm mb-3"
-                    aria-label=".form-select-sm"
-                    readOnly={user?.role === "Admin"}
-                    value={feedbackRating}
-                    onChange={(e) =>
-                      user?.role === "Customer" &&
-                      setFeedbackRating(e?.target?.value)
-                    }
-                  >
-                    <option value="None">None</option>
-                    <option value="Excellent">Excellent</option>
-                    <option value="Good">Good</option>
-                    <option value="Average">Average</option>
-                    <option value="Bad">Bad</option>
-                    <option value="Worst">Worst</option>
-                  </select>
-                </div>
+                {[
+                  ["wash_quality", "Wash Quality"],
+                  ["punctuality", "Punctuality"],
+                  ["staff_behaviour", "Staff Behaviour"],
+                ].map(([dimension, label]) => (
+                  <div className="mb-3" key={dimension}>
+                    <label htmlFor={`input-${dimension}`} className="form-label">
+                      {label}
+                    </label>
+                    <select
+                      id={`input-${dimension}`}
+                      className="form-select form-select-sm mb-3"
+                      aria-label=".form-select-sm"
+                      readOnly={user?.role === "Admin"}
+                      value={feedbackRating?.[dimension]}
+                      onChange={(e) =>
+                        user?.role === "Customer" &&
+                        setFeedbackRating({
+                          ...feedbackRating,
+                          [dimension]: Number(e?.target?.value),
+                        })
+                      }
+                    >
+                      {[5, 4, 3, 2, 1].map((stars) => (
+                        <option key={stars} value={stars}>
+                          {"\u2605".repeat(stars)}
+                        </option>
+                      ))}
+                    </select>
+                  </div>
+                ))}
                 <div className="mb-3">
                   <label htmlFor="inputFeedbackComment" className="form-label">
                     Comment
@@ -255,6 +267,7 @@ export default function Feedback() {
                     className="form-control"
                     id="inputFeedbackComment"
                     placeholder="Cutomer feedback comment"
+                    maxLength={500}
                     readOnly={user?.role === "Admin"}
                     value={feedbackComment}
                     onChange={(e) =>
@@ -21,6 +21,11 @@ export default function Header({ wallet }) {
               Home
             </Link>
           </li>
+          <li>
+            <Link to="/reviews" className="btn btn-sm nav-link px-2 link-dark">
+              Reviews
+            </Link>
+          </li>
 
           {user?.role != 2 && (
             <>
@@ -1,31 +1,29 @@
 import React, { useContext, useState, useEffect } from "react";
 import { useNavigate } from "react-router-dom";
-import { nanoid } from "nanoid";
+import { utils } from "near-api-js";
 import { AuthContext } from "../lib/Auth";
-import { isEmpty } from "../lib/utils";
+import { isEmpty, toTimeWindow } from "../lib/utils";
 
 export default function OrderForm() {
   const navigate = useNavigate();
   const { user, loader, setLoader, isSignedIn, contract } =
     useContext(AuthContext);
 
-  const [totalPrice, setTotalPrice] = useState(3);
-
-  const calculate_price = (weight) => {
-    const w = parseInt(weight, 10);
-    if (w <= 3000) {
-      console.log("p=3");
-      setTotalPrice(3);
-    } else if (w <= 7000) {
-      console.log("p=7");
-      setTotalPrice(7);
-    } else if (w <= 10000) {
-      console.log("p=10");
-      setTotalPrice(10);
-    } else {
-      console.log("invalid weight");
-    }
-  };
+  const [services, setServices] = useState([]);
+  const [serviceId, setServiceId] = useState("");
+  const [weight, setWeight] = useState(1500);
+  const [totalPrice, setTotalPrice] = useState(null);
+  const [pickupSlots, setPickupSlots] = useState([]);
+
+  const orderItems = () => [
+    {
+      service_id: serviceId,
+      garment_type: "Other",
+      quantity: 1,
+      weight_in_grams: parseInt(weight, 10),
+      special_instructions: "",
+    },
+  ];
 
   const handleSubmit = async (e) => {
     e.preventDefault();
@@ -46,21 +44,33 @@ export default function OrderForm() {
         return;
       }
 
-      const { inputDescription, inputWeight } = e.target.elements;
+      const {
+        inputDescription,
+        inputWeight,
+        inputPickup,
+        inputDelivery,
+        inputPaymentType,
+      } = e.target.elements;
 
       const isFormValid =
-        !isEmpty(inputDescription.value) && !isEmpty(inputWeight.value);
+        !isEmpty(inputDescription.value) &&
+        !isEmpty(inputWeight.value) &&
+        totalPrice !== null &&
+        pickupSlots[inputPickup.value] !== undefined &&
+        !isEmpty(inputDelivery.value);
 
       if (isFormValid) {
         try {
           setLoader(true);
 
           await contract.create_order(
-            nanoid(),
             user?.id,
             inputDescription.value,
-            inputWeight.value,
-            totalPrice
+            orderItems(),
+            pickupSlots[inputPickup.value].window,
+            toTimeWindow(inputDelivery.value),
+            totalPrice,
+            inputPaymentType.value
           );
 
           alert("order created");
@@ -88,6 +98,41 @@ export default function OrderForm() {
     }
   }, [isSignedIn, navigate]);
 
+  useEffect(() => {
+    contract
+      ?.get_service_list()
+      .then((page) => {
+        const activeServices = (page?.items || []).filter((s) => s.is_active);
+        setServices(activeServices);
+        setServiceId(activeServices[0]?.id || "");
+      })
+      .catch((error) => console.error(`[getServiceList] ${error?.message}`));
+  }, [contract]);
+
+  useEffect(() => {
+    if (!contract || isEmpty(serviceId) || !(parseInt(weight, 10) > 0)) {
+      setTotalPrice(null);
+      return;
+    }
+
+    contract
+      .calculate_order_price(orderItems())
+      .then((price) => setTotalPrice(price))
+      .catch((error) => {
+        setTotalPrice(null);
+        console.error(`[calculateOrderPrice] ${error?.message}`);
+      });
+  }, [contract, serviceId, weight]);
+
+  useEffect(() => {
+    contract
+      ?.get_available_pickup_slots()
+      .then((page) => setPickupSlots(page?.items || []))
+      .catch((error) =>
+        console.error(`[getAvailablePickupSlots] ${error?.message}`)
+      );
+  }, [contract]);
+
   return (
     <form onSubmit={(e) => loader == false && handleSubmit(e)}>
       <div className="form-floating mb-3">
@@ -105,6 +150,22 @@ export default function OrderForm() {
         </div>
       </div>
 
+      <div className="form-floating mb-3">
+        <select
+          className="form-select form-select-sm"
+          id="inputService"
+          value={serviceId}
+          onChange={(e) => setServiceId(e?.target?.value)}
+        >
+          {services.map((service) => (
+            <option key={service.id} value={service.id}>
+              {service.name}
+            </option>
+          ))}
+        </select>
+        <label htmlFor="inputService">Service</label>
+      </div>
+
       <div className="form-floating mb-3">
         <input
           type="number"
@@ -115,7 +176,7 @@ export default function OrderForm() {
           step={100}
           defaultValue={1500}
           placeholder="Total weight (Grams)"
-          onChange={(e) => calculate_price(e?.target?.value)}
+          onChange={(e) => setWeight(e?.target?.value)}
         />
         <label htmlFor="inputWeight">Total weight (grams)</label>
         <div id="weightHelp" className="form-text">
@@ -123,26 +184,79 @@ export default function OrderForm() {
         </div>
       </div>
 
+      <div className="form-floating mb-3">
+        <select
+          className="form-select form-select-sm"
+          id="inputPaymentType"
+          defaultValue="Prepaid"
+        >
+          <option value="Prepaid">Prepaid</option>
+          <option value="PayAfterWeighing">Pay after weighing</option>
+          <option value="CashOnDelivery">Cash on delivery</option>
+        </select>
+        <label htmlFor="inputPaymentType">Payment</label>
+        <div id="paymentTypeHelp" className="form-text">
+          Pay after weighing and cash on delivery orders are booked with a
+          small deposit; the rest is due once your clothes are weighed.
+        </div>
+      </div>
+
+      <div className="form-floating mb-3">
+        <select
+          className="form-select form-select-sm"
+          id="inputPickup"
+          defaultValue=""
+        >
+          <option value="" disabled>
+            Select a pickup slot
+          </option>
+          {pickupSlots.map((slot, index) => (
+            <option key={slot.window.start} value={index}>
+              {new Date(slot.window.start / 1000000).toLocaleString()} -{" "}
+              {new Date(slot.window.end / 1000000).toLocaleTimeString()}
+            </option>
+          ))}
+        </select>
+        <label htmlFor="inputPickup">Pickup slot</label>
+        <div id="pickupHelp" className="form-text">
+          Only slots with free capacity are listed.
+        </div>
+      </div>
+
       <div className="form-floating mb-3">
         <input
-          type="number"
+          type="datetime-local"
+          className="form-control form-control-sm"
+          id="inputDelivery"
+          defaultValue=""
+          placeholder="Delivery time"
+        />
+        <label htmlFor="inputDelivery">Delivery time</label>
+        <div id="deliveryHelp" className="form-text">
+          Must be after the pickup slot ends.
+        </div>
+      </div>
+
+      <div className="form-floating mb-3">
+        <input
+          type="text"
           className="form-control-plaintext form-control-plaintext-sm"
           id="inputPrice"
-          min={3}
-          max={10}
-          step={1}
-          value={totalPrice}
+          value={
+            totalPrice === null ? "" : utils.format.formatNearAmount(totalPrice)
+          }
           placeholder="Price (Near)"
           readOnly={true}
         />
         <label htmlFor="inputPrice">Price (Near)</label>
         <div id="priceHelp" className="form-text">
-          Minimum order price is 3 Near.
+          Quoted from the service catalog for the selected service and weight.
         </div>
       </div>
 
       <h6 className="text-danger">
-        Order cannot be cancelled by customer once placed.
+        Orders are fully refunded if cancelled before pickup, partially
+        refunded after pickup and cannot be cancelled once washing starts.
       </h6>
 
       <button
@@ -1,8 +1,53 @@
-import React, { useContext } from "react";
+import React, { useContext, useEffect, useState } from "react";
 import { Link } from "react-router-dom";
 import { utils } from "near-api-js";
 import { AuthContext } from "../lib/Auth";
 
+const STATUS_LABELS = {
+  PickupScheduled: "Pickup Scheduled",
+  PickedUp: "Picked Up",
+  AwaitingTopUp: "Awaiting Top-Up",
+  Washing: "Washing",
+  ReadyForDelivery: "Ready For Delivery",
+  OutForDelivery: "Out For Delivery",
+  Delivered: "Delivered",
+  Cancelled: "Cancelled",
+  Disputed: "Disputed",
+  Refunded: "Refunded",
+};
+
+function OrderStatusSelect({ order, contract, loader, onChange }) {
+  const [nextStatuses, setNextStatuses] = useState([]);
+
+  useEffect(() => {
+    contract
+      ?.get_next_order_statuses(order?.id, true)
+      .then((statuses) => setNextStatuses(statuses || []))
+      .catch((error) =>
+        console.error(`[getNextOrderStatuses] ${error?.message}`)
+      );
+  }, [contract, order?.id, order?.status]);
+
+  return (
+    <select
+      className="form-select form-select-sm"
+      aria-label=".form-select-sm"
+      value={order?.status}
+      disabled={nextStatuses.length == 0}
+      onChange={(e) => loader == false && onChange(e?.target?.value, order)}
+    >
+      <option value={order?.status} disabled>
+        {STATUS_LABELS[order?.status] || order?.status}
+      </option>
+      {nextStatuses.map((status) => (
+        <option key={status} value={status}>
+          {STATUS_LABELS[status] || status}
+        </option>
+      ))}
+    </select>
+  );
+}
+
 export default function Orders() {
   const { user, isAdmin, orders, setOrders, loader, setLoader, contract } =
     useContext(AuthContext);
@@ -72,27 +117,18 @@ export default function Orders() {
                   </td>
                   <td>
                     {isAdmin ? (
-                      <select
-                        className="form-select form-select-sm"
-                        aria-label=".form-select-sm"
-                        value={o?.status}
-                        onChange={(e) =>
-                          loader == false &&
-                          isAdmin &&
-                          handleStatusChange(e?.target?.value, o)
-                        }
-                      >
-                        <option value="Confirmed">Confirmed</option>
-                        <option value="InProgress">In Progress</option>
-                        <option value="Delivered">Delivered</option>
-                        <option value="Cancelled">Cancelled</option>
-                      </select>
+                      <OrderStatusSelect
+                        order={o}
+                        contract={contract}
+                        loader={loader}
+                        onChange={handleStatusChange}
+                      />
                     ) : (
                       o?.status
                     )}
                   </td>
                   <td>
-                    {new Date(o?.pickup_date_time / 1000000).toLocaleString()}
+                    {new Date(o?.pickup_window?.start / 1000000).toLocaleString()}
                   </td>
                   <td>
                     {o?.status == "Delivered"
@@ -0,0 +1,47 @@
+import React, { useContext, useEffect, useState } from "react";
+import { AuthContext } from "../lib/Auth";
+
+export default function Reviews() {
+  const { contract } = useContext(AuthContext);
+
+  const [reviews, setReviews] = useState([]);
+
+  useEffect(() => {
+    if (contract) {
+      contract
+        .get_reviews()
+        .then((page) => setReviews(page?.items || []))
+        .catch((error) => console.log(error?.message));
+    }
+  }, [contract, setReviews]);
+
+  return (
+    <div className="container">
+      <h4>Customer Reviews</h4>
+      {reviews.length === 0 && <p>No reviews yet.</p>}
+      {reviews.map((review, index) => (
+        <div className="card mb-3" key={index}>
+          <div className="card-body">
+            <h6 className="card-subtitle mb-2 text-muted">
+              {review?.service_ids?.join(", ")} -{" "}
+              {new Date(review?.reviewed / 1000000).toLocaleDateString()}
+            </h6>
+            <p className="card-text mb-1">
+              Wash Quality: {"★".repeat(review?.rating?.wash_quality)}
+              <br />
+              Punctuality: {"★".repeat(review?.rating?.punctuality)}
+              <br />
+              Staff Behaviour: {"★".repeat(review?.rating?.staff_behaviour)}
+            </p>
+            {review?.comment && <p className="card-text">{review.comment}</p>}
+            {review?.reply && (
+              <p className="card-text border-start ps-2 text-muted">
+                Washkart: {review.reply.body}
+              </p>
+            )}
+          </div>
+        </div>
+      ))}
+    </div>
+  );
+}
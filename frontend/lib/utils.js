@@ -9,3 +9,10 @@ export const localStorageGetItem = (key) => {
   const item = localStorage.getItem(key);
   return item ? JSON.parse(item) : null;
 };
+
+// converts a datetime-local input value to a contract time window in nanoseconds
+export const toTimeWindow = (inputValue, durationInHours = 2) => {
+  const startInMs = new Date(inputValue).getTime();
+  const endInMs = startInMs + durationInHours * 60 * 60 * 1000;
+  return { start: startInMs * 1000000, end: endInMs * 1000000 };
+};
@@ -31,6 +31,38 @@ export class NearContract {
     });
   }
 
+  async get_service_list(from_index = 0, limit = 100) {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_service_list",
+      args: { from_index, limit },
+    });
+  }
+
+  async get_reviews(from_index = 0, limit = 50) {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_reviews",
+      args: { from_index, limit },
+    });
+  }
+
+  async calculate_order_price(items) {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "calculate_order_price",
+      args: { items },
+    });
+  }
+
+  async get_available_pickup_slots(after = null, limit = 100) {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_available_pickup_slots",
+      args: { after, limit },
+    });
+  }
+
   async create_admin(account_id) {
     const result = await this.wallet.callMethod({
       contractId: this.contractId,
@@ -71,21 +103,41 @@ export class NearContract {
     return await this.wallet.getTransactionResult(result.transaction.hash);
   }
 
-  async get_orders_by_customer_id(customer_id) {
+  async get_orders_by_customer_id(customer_id, from_index = 0, limit = 100) {
     const result = await this.wallet.callMethod({
       contractId: this.contractId,
       method: "get_orders_by_customer_id",
-      args: { customer_id },
+      args: { customer_id, from_index, limit },
     });
 
     return await this.wallet.getTransactionResult(result.transaction.hash);
   }
 
-  async get_order_list() {
+  async get_order_list(from_index = 0, limit = 100) {
     const result = await this.wallet.callMethod({
       contractId: this.contractId,
       method: "get_order_list",
-      args: {},
+      args: { from_index, limit },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_orders_by_status(order_status, from_index = 0, limit = 100) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "get_orders_by_status",
+      args: { order_status, from_index, limit },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_orders_by_date_range(date_field, from, to, after = null, limit = 100) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "get_orders_by_date_range",
+      args: { date_field, from, to, after, limit },
     });
 
     return await this.wallet.getTransactionResult(result.transaction.hash);
@@ -148,19 +200,37 @@ export class NearContract {
     return await this.wallet.getTransactionResult(result.transaction.hash);
   }
 
-  async create_order(id, customer_id, description, weight_in_grams, price) {
-    const deposit = utils.format.parseNearAmount(`${price + 1}`);
-    const price_in_yocto_near = utils.format.parseNearAmount(`${price}`);
+  async create_order(
+    customer_id,
+    description,
+    items,
+    pickup_window,
+    delivery_window,
+    price_in_yocto_near,
+    payment_type = "Prepaid"
+  ) {
+    let amount = BigInt(price_in_yocto_near);
+
+    if (payment_type !== "Prepaid") {
+      const booking_deposit = BigInt(await this.get_booking_deposit());
+      amount = amount < booking_deposit ? amount : booking_deposit;
+    }
+
+    // the quoted price plus 1 Near to cover storage, the surplus is refunded
+    const deposit = (
+      amount + BigInt(utils.format.parseNearAmount(`${1}`))
+    ).toString();
 
     const result = await this.wallet.callMethod({
       contractId: this.contractId,
       method: "create_order",
       args: {
-        id,
         customer_id,
         description,
-        weight_in_grams: parseInt(weight_in_grams, 10),
-        price_in_yocto_near,
+        items,
+        pickup_window,
+        delivery_window,
+        payment_type,
       },
       deposit,
     });
@@ -168,6 +238,129 @@ export class NearContract {
     return await this.wallet.getTransactionResult(result.transaction.hash);
   }
 
+  async get_booking_deposit() {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_booking_deposit",
+    });
+  }
+
+  async confirm_cash_payment(order_id, amount_in_yocto_near) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "confirm_cash_payment",
+      args: { order_id, amount_in_yocto_near },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_accepted_tokens() {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_accepted_tokens",
+    });
+  }
+
+  async deposit_token_storage(deposit) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "deposit_token_storage",
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_token_storage_balance(account_id) {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_token_storage_balance",
+      args: { account_id },
+    });
+  }
+
+  async claim_tokens(token_id) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "claim_tokens",
+      args: { token_id },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async create_order_with_token(
+    token_id,
+    amount,
+    description,
+    items,
+    pickup_window,
+    delivery_window
+  ) {
+    const msg = JSON.stringify({
+      CreateOrder: { description, items, pickup_window, delivery_window },
+    });
+
+    const result = await this.wallet.callMethod({
+      contractId: token_id,
+      method: "ft_transfer_call",
+      args: { receiver_id: this.contractId, amount, msg },
+      deposit: "1",
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async top_up_order_with_token(token_id, amount, order_id) {
+    const msg = JSON.stringify({ TopUpOrder: { order_id } });
+
+    const result = await this.wallet.callMethod({
+      contractId: token_id,
+      method: "ft_transfer_call",
+      args: { receiver_id: this.contractId, amount, msg },
+      deposit: "1",
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async modify_order(
+    order_id,
+    description,
+    items,
+    address,
+    pickup_window,
+    delivery_window,
+    price_increase = 0
+  ) {
+    const deposit = utils.format.parseNearAmount(`${price_increase + 1}`);
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "modify_order",
+      args: {
+        order_id,
+        description,
+        items,
+        address,
+        pickup_window,
+        delivery_window,
+      },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_next_order_statuses(order_id, admin_only = false) {
+    return await this.wallet.viewMethod({
+      contractId: this.contractId,
+      method: "get_next_order_statuses",
+      args: { order_id, admin_only },
+    });
+  }
+
   async update_order_status(order_id, order_status) {
     const result = await this.wallet.callMethod({
       contractId: this.contractId,
@@ -181,19 +374,232 @@ export class NearContract {
     return await this.wallet.getTransactionResult(result.transaction.hash);
   }
 
-  async submit_feedback(
+  async update_order_statuses(updates) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "update_order_statuses",
+      args: { updates },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async cancel_order(order_id, reason = null) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "cancel_order",
+      args: {
+        order_id,
+        reason,
+      },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async record_order_weight(order_id, weights_in_grams) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "record_order_weight",
+      args: { order_id, weights_in_grams },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async top_up_order(order_id, amount_due_in_yocto_near) {
+    // the amount due plus 1 Near to cover storage, the surplus is refunded
+    const deposit = (
+      BigInt(amount_due_in_yocto_near) +
+      BigInt(utils.format.parseNearAmount(`${1}`))
+    ).toString();
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "top_up_order",
+      args: { order_id },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async create_subscription(
+    description,
+    items,
+    cadence,
+    pickup_window,
+    delivery_window,
+    balance
+  ) {
+    const deposit = utils.format.parseNearAmount(`${balance}`);
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "create_subscription",
+      args: {
+        description,
+        items,
+        cadence,
+        pickup_window,
+        delivery_window,
+      },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async fund_subscription(amount) {
+    const deposit = utils.format.parseNearAmount(`${amount}`);
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "fund_subscription",
+      args: {},
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async cancel_subscription() {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "cancel_subscription",
+      args: {},
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_subscription(customer_id) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "get_subscription",
+      args: { customer_id },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async post_order_message(order_id, body) {
+    const deposit = utils.format.parseNearAmount(`${1}`);
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "post_order_message",
+      args: { order_id, body },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async get_order_messages(order_id, from_index = 0, limit = 100) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "get_order_messages",
+      args: { order_id, from_index, limit },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async attach_order_evidence(order_id, hash, label) {
+    const deposit = utils.format.parseNearAmount(`${1}`);
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "attach_order_evidence",
+      args: { order_id, hash, label },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async file_claim(
     order_id,
-    customer_feedback,
-    customer_feedback_comment = ""
+    claim_type,
+    item_indexes,
+    description,
+    requested_in_yocto_near
   ) {
     const deposit = utils.format.parseNearAmount(`${1}`);
 
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "file_claim",
+      args: {
+        order_id,
+        claim_type,
+        item_indexes,
+        description,
+        requested_in_yocto_near,
+      },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async settle_order(order_id) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "settle_order",
+      args: { order_id },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async set_feedback_comment_hidden(order_id, is_hidden) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "set_feedback_comment_hidden",
+      args: { order_id, is_hidden },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async reply_to_review(order_id, body) {
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "reply_to_review",
+      args: { order_id, body },
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async open_dispute(order_id, reason, evidence_hashes = []) {
+    const deposit = utils.format.parseNearAmount(`${1}`);
+
+    const result = await this.wallet.callMethod({
+      contractId: this.contractId,
+      method: "open_dispute",
+      args: {
+        order_id,
+        reason,
+        evidence_hashes,
+      },
+      deposit,
+    });
+
+    return await this.wallet.getTransactionResult(result.transaction.hash);
+  }
+
+  async submit_feedback(order_id, rating, customer_feedback_comment = "") {
+    const deposit = utils.format.parseNearAmount(`${1}`);
+
     const result = await this.wallet.callMethod({
       contractId: this.contractId,
       method: "submit_feedback",
       args: {
         order_id,
-        customer_feedback,
+        rating,
         customer_feedback_comment,
       },
       deposit,
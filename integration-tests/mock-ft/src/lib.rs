@@ -0,0 +1,106 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{env, ext_contract, near_bindgen, require, AccountId, Gas, PanicOnDefault, PromiseOrValue, PromiseResult};
+
+const GAS_FOR_FT_ON_TRANSFER: Gas = Gas(50_000_000_000_000);
+const GAS_FOR_RESOLVE_TRANSFER: Gas = Gas(10_000_000_000_000);
+
+#[ext_contract(ext_ft_receiver)]
+pub trait FungibleTokenReceiver {
+    fn ft_on_transfer(&mut self, sender_id: AccountId, amount: U128, msg: String) -> PromiseOrValue<U128>;
+}
+
+#[ext_contract(ext_self)]
+pub trait FungibleTokenResolver {
+    fn ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128;
+}
+
+// A minimal NEP-141 token for the sandbox integration tests: no storage registration and no metadata.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockFungibleToken {
+    balances: LookupMap<AccountId, u128>,
+}
+
+#[near_bindgen]
+impl MockFungibleToken {
+    #[init]
+    pub fn new() -> Self {
+        Self { balances: LookupMap::new(b"b") }
+    }
+
+    pub fn mint(&mut self, account_id: AccountId, amount: U128) {
+        let balance = self.balances.get(&account_id).unwrap_or(0);
+        self.balances.insert(&account_id, &(balance + amount.0));
+    }
+
+    pub fn ft_balance_of(&self, account_id: AccountId) -> U128 {
+        U128(self.balances.get(&account_id).unwrap_or(0))
+    }
+
+    #[payable]
+    pub fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>) {
+        require!(env::attached_deposit() == 1, "Requires attached deposit of exactly 1 yoctoNEAR");
+        self.internal_transfer(&env::predecessor_account_id(), &receiver_id, amount.0);
+
+        if let Some(memo) = memo {
+            env::log_str(&format!("Memo: {}", memo));
+        }
+    }
+
+    #[payable]
+    pub fn ft_transfer_call(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>, msg: String) -> PromiseOrValue<U128> {
+        require!(env::attached_deposit() == 1, "Requires attached deposit of exactly 1 yoctoNEAR");
+        let sender_id = env::predecessor_account_id();
+        self.internal_transfer(&sender_id, &receiver_id, amount.0);
+
+        if let Some(memo) = memo {
+            env::log_str(&format!("Memo: {}", memo));
+        }
+
+        ext_ft_receiver::ext(receiver_id.clone())
+            .with_static_gas(GAS_FOR_FT_ON_TRANSFER)
+            .ft_on_transfer(sender_id.clone(), amount, msg)
+            .then(
+                ext_self::ext(env::current_account_id())
+                    .with_static_gas(GAS_FOR_RESOLVE_TRANSFER)
+                    .ft_resolve_transfer(sender_id, receiver_id, amount),
+            )
+            .into()
+    }
+
+    #[private]
+    pub fn ft_resolve_transfer(&mut self, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128 {
+        let unused: u128 = match env::promise_result(0) {
+            PromiseResult::Successful(value) => match near_sdk::serde_json::from_slice::<U128>(&value) {
+                Ok(unused) => std::cmp::min(amount.0, unused.0),
+                Err(_) => amount.0,
+            },
+            _ => amount.0,
+        };
+
+        if unused > 0 {
+            let receiver_balance = self.balances.get(&receiver_id).unwrap_or(0);
+            let refund = std::cmp::min(unused, receiver_balance);
+            self.internal_transfer(&receiver_id, &sender_id, refund);
+            return U128(amount.0 - refund);
+        }
+
+        amount
+    }
+}
+
+impl MockFungibleToken {
+    fn internal_transfer(&mut self, sender_id: &AccountId, receiver_id: &AccountId, amount: u128) {
+        require!(amount > 0, "The amount should be a positive number");
+
+        let sender_balance = self.balances.get(sender_id).unwrap_or(0);
+        require!(sender_balance >= amount, "The account doesn't have enough balance");
+
+        self.balances.insert(sender_id, &(sender_balance - amount));
+
+        let receiver_balance = self.balances.get(receiver_id).unwrap_or(0);
+        self.balances.insert(receiver_id, &(receiver_balance + amount));
+    }
+}
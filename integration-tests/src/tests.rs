@@ -2,16 +2,20 @@ use std::{env, fs};
 use near_units::parse_near;
 use serde_json::json;
 use workspaces::prelude::*;
-use workspaces::{network::Sandbox, Account, Contract, Worker};
+use workspaces::{network::Sandbox, result::CallExecutionDetails, Account, Contract, Worker};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let wasm_arg: &str = &(env::args().nth(1).unwrap());
     let wasm_filepath = fs::canonicalize(env::current_dir()?.join(wasm_arg))?;
+    let ft_wasm_arg: &str = &(env::args().nth(2).unwrap());
+    let ft_wasm_filepath = fs::canonicalize(env::current_dir()?.join(ft_wasm_arg))?;
 
     let worker = workspaces::sandbox().await?;
     let wasm = std::fs::read(wasm_filepath)?;
     let contract = worker.dev_deploy(&wasm).await?;
+    let ft_wasm = std::fs::read(ft_wasm_filepath)?;
+    let ft = worker.dev_deploy(&ft_wasm).await?;
 
     // create accounts
     let account = worker.dev_create_account().await?;
@@ -21,48 +25,205 @@ async fn main() -> anyhow::Result<()> {
         .transact()
         .await?
         .into_result()?;
+    let admin = account
+        .create_subaccount(&worker, "admin")
+        .initial_balance(parse_near!("30 N"))
+        .transact()
+        .await?
+        .into_result()?;
+
+    setup_token_payments(&admin, &alice, &contract, &ft, &worker).await?;
 
     // begin tests
-    test_default_message(&alice, &contract, &worker).await?;
-    test_changes_message(&alice, &contract, &worker).await?;
+    test_pays_order_with_tokens(&alice, &contract, &ft, &worker).await?;
+    test_refunds_cancelled_token_order(&alice, &contract, &ft, &worker).await?;
     Ok(())
 }
 
-async fn test_default_message(
-    user: &Account,
+/// Fails a call whose receipts failed, which transact() only reports for the transaction itself.
+trait IntoResult: Sized {
+    fn into_result(self) -> anyhow::Result<Self>;
+}
+
+impl IntoResult for CallExecutionDetails {
+    fn into_result(self) -> anyhow::Result<Self> {
+        if let Some(failure) = self.receipt_failures().first() {
+            anyhow::bail!("Receipt failed: {:?}", failure);
+        }
+        Ok(self)
+    }
+}
+
+// 2100-01-01, so that pickup windows are always in the future of the sandbox
+const FUTURE_NS: u64 = 4_102_444_800_000_000_000;
+const HOUR_NS: u64 = 3_600_000_000_000;
+
+async fn setup_token_payments(
+    admin: &Account,
+    customer: &Account,
     contract: &Contract,
+    ft: &Contract,
     worker: &Worker<Sandbox>,
 ) -> anyhow::Result<()> {
-    let message: String = user
-        .call(&worker, contract.id(), "get_greeting")
+    contract
+        .call(&worker, "init")
         .args_json(json!({}))?
         .transact()
         .await?
-        .json()?;
+        .into_result()?;
+    contract
+        .call(&worker, "create_admin")
+        .args_json(json!({"account_id": admin.id()}))?
+        .transact()
+        .await?
+        .into_result()?;
+    ft.call(&worker, "new")
+        .args_json(json!({}))?
+        .transact()
+        .await?
+        .into_result()?;
+    ft.call(&worker, "mint")
+        .args_json(json!({"account_id": customer.id(), "amount": "100000000"}))?
+        .transact()
+        .await?
+        .into_result()?;
+
+    admin
+        .call(&worker, contract.id(), "create_service")
+        .args_json(json!({
+            "service_id": "wash_and_fold",
+            "name": "Wash & Fold",
+            "price_per_kg_in_yocto_near": parse_near!("1 N").to_string(),
+            "price_per_item_in_yocto_near": "0",
+        }))?
+        .transact()
+        .await?
+        .into_result()?;
+    admin
+        .call(&worker, contract.id(), "create_pickup_slot")
+        .args_json(json!({
+            "window": {"start": FUTURE_NS, "end": FUTURE_NS + 2 * HOUR_NS},
+            "max_orders": 0,
+            "max_weight_in_grams": 0,
+        }))?
+        .transact()
+        .await?
+        .into_result()?;
+    // 1 NEAR is worth 2 USDC, with 6 decimals
+    admin
+        .call(&worker, contract.id(), "add_accepted_token")
+        .args_json(json!({"token_id": ft.id(), "symbol": "USDC", "units_per_near": "2000000"}))?
+        .transact()
+        .await?
+        .into_result()?;
 
-    assert_eq!(message, "Hello".to_string());
-    println!("      Passed ✅ gets default message");
+    customer
+        .call(&worker, contract.id(), "create_customer")
+        .args_json(json!({
+            "account_id": customer.id(),
+            "name": "alice",
+            "phone": "",
+            "email": "",
+            "full_address": "123 street, NY",
+            "landmark": "",
+            "google_plus_code_address": "",
+        }))?
+        .deposit(parse_near!("1 N"))
+        .transact()
+        .await?
+        .into_result()?;
+    customer
+        .call(&worker, contract.id(), "deposit_token_storage")
+        .args_json(json!({}))?
+        .deposit(parse_near!("1 N"))
+        .transact()
+        .await?
+        .into_result()?;
     Ok(())
 }
 
-async fn test_changes_message(
+async fn ft_balance_of(
+    account_id: &workspaces::AccountId,
+    ft: &Contract,
+    worker: &Worker<Sandbox>,
+) -> anyhow::Result<u128> {
+    let balance: String = ft
+        .view(
+            &worker,
+            "ft_balance_of",
+            json!({"account_id": account_id}).to_string().into_bytes(),
+        )
+        .await?
+        .json()?;
+
+    Ok(balance.parse()?)
+}
+
+async fn test_pays_order_with_tokens(
     user: &Account,
     contract: &Contract,
+    ft: &Contract,
     worker: &Worker<Sandbox>,
 ) -> anyhow::Result<()> {
-    user.call(&worker, contract.id(), "set_greeting")
-        .args_json(json!({"message": "Howdy"}))?
+    let msg = json!({
+        "CreateOrder": {
+            "description": "2 pants",
+            "items": [{
+                "service_id": "wash_and_fold",
+                "garment_type": "Pants",
+                "quantity": 2,
+                "weight_in_grams": 4000,
+                "special_instructions": "",
+            }],
+            "pickup_window": {"start": FUTURE_NS, "end": FUTURE_NS + 2 * HOUR_NS},
+            "delivery_window": {"start": FUTURE_NS + 24 * HOUR_NS, "end": FUTURE_NS + 26 * HOUR_NS},
+        }
+    });
+
+    user.call(&worker, ft.id(), "ft_transfer_call")
+        .args_json(json!({"receiver_id": contract.id(), "amount": "10000000", "msg": msg.to_string()}))?
+        .deposit(1)
+        .max_gas()
         .transact()
-        .await?;
+        .await?
+        .into_result()?;
 
-    let message: String = user
-        .call(&worker, contract.id(), "get_greeting")
-        .args_json(json!({}))?
+    // 4 kg at 1 NEAR per kg costs 8 USDC; the other 2 USDC are returned by ft_resolve_transfer
+    assert_eq!(ft_balance_of(user.id(), &ft, &worker).await?, 92_000_000);
+    assert_eq!(ft_balance_of(contract.id(), &ft, &worker).await?, 8_000_000);
+
+    let order: serde_json::Value = user
+        .call(&worker, contract.id(), "get_order_by_id")
+        .args_json(json!({"order_id": "WK-1"}))?
         .transact()
         .await?
         .json()?;
 
-    assert_eq!(message, "Howdy".to_string());
-    println!("      Passed ✅ changes message");
+    assert_eq!(order["payment_type"], "FungibleToken");
+    println!("      Passed ✅ pays order with tokens");
+    Ok(())
+}
+
+async fn test_refunds_cancelled_token_order(
+    user: &Account,
+    contract: &Contract,
+    ft: &Contract,
+    worker: &Worker<Sandbox>,
+) -> anyhow::Result<()> {
+    let balance_before = ft_balance_of(user.id(), &ft, &worker).await?;
+
+    user.call(&worker, contract.id(), "cancel_order")
+        .args_json(json!({"order_id": "WK-1"}))?
+        .max_gas()
+        .transact()
+        .await?
+        .into_result()?;
+
+    // the refund comes back in full in tokens; storage is charged to the NEAR token storage balance
+    let balance_after = ft_balance_of(user.id(), &ft, &worker).await?;
+
+    assert_eq!(balance_after, balance_before + 8_000_000);
+    assert_eq!(ft_balance_of(contract.id(), &ft, &worker).await?, 0);
+    println!("      Passed ✅ refunds cancelled token order in tokens");
     Ok(())
-}
\ No newline at end of file
+}